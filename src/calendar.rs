@@ -0,0 +1,331 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Deserialize;
+use validator::{Validate, ValidationError};
+
+use crate::contributor::Contributor;
+use crate::error::KapacitetError;
+
+// Tolerance for rounding when adding up effort
+static EFFORT_EPSILON: f64 = 0.001;
+
+/// Time off for a single contributor, or a holiday for the whole team when no contributor
+/// is given. Both dates are included.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Validate)]
+#[validate(schema(function = "validate_absence"))]
+pub struct Absence {
+    #[serde(default)]
+    pub contributor: Option<String>,
+    pub start_date: NaiveDate,
+    /// Defaults to the start date for absences of a single day
+    #[serde(default)]
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Team holidays and time off of individual contributors. Weekends are never working days.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Calendar {
+    pub absences: Vec<Absence>,
+}
+
+impl Absence {
+    pub fn new(
+        contributor: Option<&str>,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        description: &str,
+    ) -> Absence {
+        return Absence {
+            contributor: contributor.map(String::from),
+            start_date,
+            end_date: Some(end_date),
+            description: String::from(description),
+        };
+    }
+
+    pub fn get_end_date(&self) -> NaiveDate {
+        self.end_date.unwrap_or(self.start_date)
+    }
+
+    fn covers(&self, date: NaiveDate) -> bool {
+        return self.start_date <= date && date <= self.get_end_date();
+    }
+}
+
+impl Calendar {
+    pub fn new(absences: Vec<Absence>) -> Calendar {
+        return Calendar { absences };
+    }
+
+    /// Whether the team works on the given day, i.e. it's neither a weekend nor a holiday.
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            return false;
+        }
+        return !self
+            .absences
+            .iter()
+            .any(|absence| absence.contributor.is_none() && absence.covers(date));
+    }
+
+    /// Whether the contributor works on the given day, taking their availability window and
+    /// time off into account.
+    pub fn is_available(&self, contributor: &Contributor, date: NaiveDate) -> bool {
+        if !self.is_working_day(date) || !contributor.is_employed_on(date) {
+            return false;
+        }
+        return !self.absences.iter().any(|absence| {
+            absence.contributor.as_deref() == Some(contributor.name.as_str())
+                && absence.covers(date)
+        });
+    }
+
+    pub fn next_working_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !self.is_working_day(date) {
+            date += Duration::days(1);
+        }
+        return date;
+    }
+
+    /// Returns the last working day of a stretch of `working_days` days starting at `start`.
+    pub fn add_working_days(&self, start: NaiveDate, working_days: i64) -> NaiveDate {
+        let mut date = self.next_working_day(start);
+        let mut remaining = working_days - 1;
+        while remaining > 0 {
+            date = self.next_working_day(date + Duration::days(1));
+            remaining -= 1;
+        }
+        return date;
+    }
+
+    /// Returns the last day of work on an item of `effort` working days for a single
+    /// full-time contributor. Every day each contributor of the team is available adds
+    /// their allocation to the finished effort. When the whole team has left before the
+    /// work is done, the last day anyone worked on it is returned.
+    pub fn finish_date(&self, start: NaiveDate, effort: f64, team: &[Contributor]) -> NaiveDate {
+        let mut date = self.next_working_day(start);
+        let mut last_worked = date;
+        let mut done = 0.0;
+        loop {
+            let progress: f64 = team
+                .iter()
+                .filter(|contributor| self.is_available(contributor, date))
+                .map(|contributor| contributor.get_allocation())
+                .sum();
+            if progress > 0.0 {
+                done += progress;
+                last_worked = date;
+            }
+            if done >= effort - EFFORT_EPSILON {
+                return date;
+            }
+            let gone = team.iter().all(|contributor| {
+                contributor
+                    .available_until
+                    .is_some_and(|until| until <= date)
+            });
+            if team.is_empty() || gone {
+                warn!("The team leaves on {last_worked} before the work is done");
+                return last_worked;
+            }
+            date = self.next_working_day(date + Duration::days(1));
+        }
+    }
+
+    pub fn extend(&mut self, other: Calendar) {
+        self.absences.extend(other.absences);
+    }
+}
+
+/// Reads the events of an iCalendar file as absences. Events whose summary starts with a
+/// contributor name followed by a colon, like `Ada: Vacation`, are time off for that
+/// contributor. All other events are team holidays.
+pub fn parse_ics(file_path: &str, contents: &str) -> Result<Vec<Absence>, KapacitetError> {
+    let malformed =
+        |reason: String| KapacitetError::MalformedCalendar(String::from(file_path), reason);
+
+    // Long lines are folded by starting the continuation with a space or a tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(String::from(line)),
+        }
+    }
+
+    let mut absences: Vec<Absence> = Vec::new();
+    let mut event: Option<IcsEvent> = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (property, parameters) = name.split_once(';').unwrap_or((name, ""));
+        match (property.to_uppercase().as_str(), event.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(IcsEvent::default());
+            }
+            ("END", Some(ics_event)) if value.eq_ignore_ascii_case("VEVENT") => {
+                absences.push(ics_event.to_absence().map_err(malformed)?);
+                event = None;
+            }
+            ("DTSTART", Some(ics_event)) => {
+                ics_event.start = Some(parse_ics_date(parameters, value).map_err(malformed)?);
+            }
+            ("DTEND", Some(ics_event)) => {
+                ics_event.end = Some(parse_ics_date(parameters, value).map_err(malformed)?);
+            }
+            ("SUMMARY", Some(ics_event)) => {
+                ics_event.summary = value
+                    .replace("\\,", ",")
+                    .replace("\\;", ";")
+                    .replace("\\n", " ");
+            }
+            ("RRULE", Some(ics_event)) => {
+                warn!(
+                    "Recurring event {0} in {file_path} is only counted once",
+                    ics_event.summary
+                );
+            }
+            _ => {}
+        }
+    }
+    return Ok(absences);
+}
+
+// Dates are paired with whether they are all-day dates without a time
+#[derive(Default)]
+struct IcsEvent {
+    start: Option<(NaiveDate, bool)>,
+    end: Option<(NaiveDate, bool)>,
+    summary: String,
+}
+
+impl IcsEvent {
+    fn to_absence(&self) -> Result<Absence, String> {
+        let Some((start_date, _)) = self.start else {
+            return Err(format!("The event {0} has no DTSTART", self.summary));
+        };
+        let end_date = match self.end {
+            // The end of all-day events is the day after the event
+            Some((date, true)) => NaiveDate::max(start_date, date - Duration::days(1)),
+            Some((date, false)) => date,
+            None => start_date,
+        };
+        let (contributor, description) = match self.summary.split_once(':') {
+            Some((contributor, description)) => (Some(contributor.trim()), description.trim()),
+            None => (None, self.summary.trim()),
+        };
+        return Ok(Absence::new(contributor, start_date, end_date, description));
+    }
+}
+
+// Returns the date and whether it's an all-day date without a time
+fn parse_ics_date(parameters: &str, value: &str) -> Result<(NaiveDate, bool), String> {
+    let all_day = parameters.to_uppercase().contains("VALUE=DATE") && !value.contains('T')
+        || value.len() == 8;
+    match value
+        .get(0..8)
+        .map(|date| NaiveDate::parse_from_str(date, "%Y%m%d"))
+    {
+        Some(Ok(date)) => {
+            return Ok((date, all_day));
+        }
+        _ => {
+            return Err(format!("Could not parse the date {value}"));
+        }
+    }
+}
+
+fn validate_absence(absence: &Absence) -> Result<(), ValidationError> {
+    if absence.get_end_date() < absence.start_date {
+        return Err(ValidationError::new(
+            "The end date cannot be before the start date.",
+        ));
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::calendar::{parse_ics, Absence, Calendar};
+    use crate::contributor::Contributor;
+
+    #[test]
+    fn skips_weekends_and_holidays() {
+        // 2022-10-14 is a Friday
+        let friday = NaiveDate::from_ymd_opt(2022, 10, 14).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let calendar = Calendar::default();
+        assert_eq!(calendar.add_working_days(friday, 1), friday);
+        assert_eq!(calendar.add_working_days(friday, 2), monday);
+        assert_eq!(
+            calendar.add_working_days(friday + Duration::days(1), 5),
+            NaiveDate::from_ymd_opt(2022, 10, 21).unwrap()
+        );
+
+        let calendar = Calendar::new(vec![Absence::new(None, monday, monday, "Holiday")]);
+        assert_eq!(
+            calendar.next_working_day(friday + Duration::days(1)),
+            monday + Duration::days(1)
+        );
+    }
+
+    #[test]
+    fn finishes_later_when_contributors_are_away() {
+        let monday = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let team = vec![Contributor::new(String::from("IC1"), 3)];
+        let calendar = Calendar::default();
+        assert_eq!(
+            calendar.finish_date(monday, 5.0, &team),
+            NaiveDate::from_ymd_opt(2022, 10, 21).unwrap()
+        );
+
+        let calendar = Calendar::new(vec![Absence::new(
+            Some("IC1"),
+            monday + Duration::days(1),
+            monday + Duration::days(2),
+            "Vacation",
+        )]);
+        assert_eq!(
+            calendar.finish_date(monday, 5.0, &team),
+            NaiveDate::from_ymd_opt(2022, 10, 25).unwrap()
+        );
+
+        let mut leaver = Contributor::new(String::from("IC2"), 3);
+        leaver.available_until = Some(monday + Duration::days(1));
+        assert_eq!(
+            Calendar::default().finish_date(monday, 5.0, &[leaver]),
+            monday + Duration::days(1)
+        );
+    }
+
+    #[test]
+    fn reads_holidays_and_time_off_from_ics() {
+        let absences = parse_ics(
+            "team.ics",
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20221224\r\nDTEND;VALUE=DATE:20221227\r\nSUMMARY:Christmas\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20221031T090000Z\r\nDTEND:20221101T170000Z\r\nSUMMARY:IC1: Moving\r\n  house\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            absences,
+            vec![
+                Absence::new(
+                    None,
+                    NaiveDate::from_ymd_opt(2022, 12, 24).unwrap(),
+                    NaiveDate::from_ymd_opt(2022, 12, 26).unwrap(),
+                    "Christmas"
+                ),
+                Absence::new(
+                    Some("IC1"),
+                    NaiveDate::from_ymd_opt(2022, 10, 31).unwrap(),
+                    NaiveDate::from_ymd_opt(2022, 11, 1).unwrap(),
+                    "Moving house"
+                ),
+            ]
+        );
+    }
+}
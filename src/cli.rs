@@ -0,0 +1,272 @@
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use kapacitet::input::InputFormat;
+use kapacitet::report::CsvLayout;
+use kapacitet::schedule::Strategy;
+use kapacitet::scoring::Model;
+use kapacitet::skill::SkillMatching;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "kapacitet",
+    version,
+    about = "A little tool for roadmap and capacity planning"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+    /// Path to a TOML config file, replacing the project and user config files
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<String>,
+    /// Path to a project file with the team, roadmap and settings, defaults to kapacitet.toml
+    /// when input files are missing
+    #[arg(long, value_name = "PATH", global = true)]
+    pub project: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Assign contributors to roadmap items and schedule them
+    Plan(PlanArgs),
+    /// Calculate the urgency score of each roadmap item
+    Score(ScoreArgs),
+    /// Break down how the urgency score of each roadmap item is calculated
+    Explain(ExplainArgs),
+    /// Check the config and input files and report every problem, exits with 1 if there are any
+    Validate(ValidateArgs),
+    /// Compare two plans: added and removed items, rank and urgency changes and contributor moves
+    Diff(DiffArgs),
+    /// Create template config, contributors and roadmap files
+    Init(InitArgs),
+    /// Add contributors and roadmap items through prompts on the command line
+    Interactive(InteractiveArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PlanArgs {
+    /// Path to the contributors file, instead of the contributors of the project
+    #[arg(long, value_name = "PATH")]
+    pub contributors: Option<String>,
+    /// Path to the roadmap file, instead of the roadmap of the project
+    #[arg(long, value_name = "PATH")]
+    pub roadmap: Option<String>,
+    /// Format of the contributors and roadmap files: csv, json, yaml or toml. Detected from
+    /// the file extensions by default
+    #[arg(long = "input-format", value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
+    /// Scoring model to calculate urgency with: weighted, wsjf, rice or moscow
+    #[arg(long, value_name = "MODEL")]
+    pub model: Option<Model>,
+    /// Seed for assigning contributors, so that the same inputs give the same plan
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u64>,
+    /// How to assign contributors: optimal or greedy
+    #[arg(long, value_name = "STRATEGY")]
+    pub strategy: Option<Strategy>,
+    /// Whether contributors should (prefer) or must (require) have the required skills
+    #[arg(long = "skill-matching", value_name = "MODE")]
+    pub skill_matching: Option<SkillMatching>,
+    /// Path to a CSV or iCalendar (.ics) file with holidays and time off, can be repeated
+    #[arg(long, value_name = "PATH")]
+    pub calendar: Vec<String>,
+    /// Add columns breaking down the urgency score to csv, json, table or markdown output
+    #[arg(long)]
+    pub explain: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    pub output: OutputFormat,
+    /// Character separating the fields of CSV output
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_delimiter)]
+    pub delimiter: u8,
+    /// Layout of CSV output: one row per item (wide) or per assignment (long)
+    #[arg(long, value_name = "LAYOUT", default_value_t = CsvLayout::Wide)]
+    pub layout: CsvLayout,
+    /// Date to plan as of (YYYY-mm-dd), defaults to the current date
+    #[arg(long = "as-of", visible_alias = "today", value_name = "DATE")]
+    pub as_of: Option<NaiveDate>,
+    /// Path to the CSV or JSON output of an earlier run, whose assignments are kept unless moving
+    /// someone gains more than the improvement threshold
+    #[arg(long, value_name = "PATH")]
+    pub previous: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ScoreArgs {
+    /// Path to the roadmap file, instead of the roadmap of the project
+    #[arg(long, value_name = "PATH")]
+    pub roadmap: Option<String>,
+    /// Format of the contributors and roadmap files: csv, json, yaml or toml. Detected from
+    /// the file extensions by default
+    #[arg(long = "input-format", value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
+    /// Scoring model to calculate urgency with: weighted, wsjf, rice or moscow
+    #[arg(long, value_name = "MODEL")]
+    pub model: Option<Model>,
+    /// Add columns breaking down the urgency score to csv, json, table or markdown output
+    #[arg(long)]
+    pub explain: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    pub output: OutputFormat,
+    /// Character separating the fields of CSV output
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_delimiter)]
+    pub delimiter: u8,
+    /// Date to score as of (YYYY-mm-dd), defaults to the current date
+    #[arg(long = "as-of", visible_alias = "today", value_name = "DATE")]
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExplainArgs {
+    /// Path to the roadmap file, instead of the roadmap of the project
+    #[arg(long, value_name = "PATH")]
+    pub roadmap: Option<String>,
+    /// Format of the contributors and roadmap files: csv, json, yaml or toml. Detected from
+    /// the file extensions by default
+    #[arg(long = "input-format", value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
+    /// Only explain the roadmap item with this name
+    #[arg(long, value_name = "NAME")]
+    pub item: Option<String>,
+    /// Scoring model to calculate urgency with: weighted, wsjf, rice or moscow
+    #[arg(long, value_name = "MODEL")]
+    pub model: Option<Model>,
+    /// Date to score as of (YYYY-mm-dd), defaults to the current date
+    #[arg(long = "as-of", visible_alias = "today", value_name = "DATE")]
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    /// Path to the contributors file
+    #[arg(long, value_name = "PATH")]
+    pub contributors: Option<String>,
+    /// Path to the roadmap file
+    #[arg(long, value_name = "PATH")]
+    pub roadmap: Option<String>,
+    /// Format of the contributors and roadmap files: csv, json, yaml or toml. Detected from
+    /// the file extensions by default
+    #[arg(long = "input-format", value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
+    /// Path to a CSV or iCalendar (.ics) file with holidays and time off, can be repeated
+    #[arg(long, value_name = "PATH")]
+    pub calendar: Vec<String>,
+    /// Date to validate as of (YYYY-mm-dd), defaults to the current date
+    #[arg(long = "as-of", visible_alias = "today", value_name = "DATE")]
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Path to the CSV or JSON output of the earlier plan
+    #[arg(value_name = "OLD")]
+    pub old: String,
+    /// Path to the CSV or JSON output of the later plan
+    #[arg(value_name = "NEW")]
+    pub new: String,
+    #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+    pub output: DiffFormat,
+    /// Character separating the fields of CSV plans
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_delimiter)]
+    pub delimiter: u8,
+}
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Directory to create the template files in
+    #[arg(long, value_name = "PATH", default_value = ".")]
+    pub dir: String,
+    /// Overwrite existing files
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct InteractiveArgs {
+    /// Path to a CSV or iCalendar (.ics) file with holidays and time off, can be repeated
+    #[arg(long, value_name = "PATH")]
+    pub calendar: Vec<String>,
+    /// Scoring model to calculate urgency with: weighted, wsjf, rice or moscow
+    #[arg(long, value_name = "MODEL")]
+    pub model: Option<Model>,
+    /// Seed for assigning contributors, so that the same inputs give the same plan
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u64>,
+    /// How to assign contributors: optimal or greedy
+    #[arg(long, value_name = "STRATEGY")]
+    pub strategy: Option<Strategy>,
+    /// Whether contributors should (prefer) or must (require) have the required skills
+    #[arg(long = "skill-matching", value_name = "MODE")]
+    pub skill_matching: Option<SkillMatching>,
+    /// Add columns breaking down the urgency score to csv, json, table or markdown output
+    #[arg(long)]
+    pub explain: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    pub output: OutputFormat,
+    /// Character separating the fields of CSV output
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_delimiter)]
+    pub delimiter: u8,
+    /// Layout of CSV output: one row per item (wide) or per assignment (long)
+    #[arg(long, value_name = "LAYOUT", default_value_t = CsvLayout::Wide)]
+    pub layout: CsvLayout,
+    /// Date to plan as of (YYYY-mm-dd), defaults to the current date
+    #[arg(long = "as-of", visible_alias = "today", value_name = "DATE")]
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// CSV, with the values used for the run printed to stderr
+    Csv,
+    /// JSON following the documented schema of the plan report
+    Json,
+    /// Self-contained HTML page with a Gantt chart of the items and contributors
+    Html,
+    /// SVG Gantt chart of the items and contributors
+    Svg,
+    /// Mermaid Gantt diagram with a section per urgency band
+    Mermaid,
+    /// PlantUML Gantt diagram with a separator per urgency band
+    Plantuml,
+    /// Table with aligned columns and urgency bars for reading in a terminal
+    Table,
+    /// GitHub-flavored Markdown table
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Whether the format has room for the breakdown of urgency scores added by `--explain`.
+    pub fn shows_breakdown(&self) -> bool {
+        match self {
+            OutputFormat::Csv
+            | OutputFormat::Json
+            | OutputFormat::Table
+            | OutputFormat::Markdown => {
+                return true;
+            }
+            OutputFormat::Html
+            | OutputFormat::Svg
+            | OutputFormat::Mermaid
+            | OutputFormat::Plantuml => {
+                return false;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    /// Summary for reading in the terminal
+    Text,
+    /// GitHub-flavored Markdown tables
+    Markdown,
+    /// JSON with every added, removed and changed item and every move
+    Json,
+}
+
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value.as_bytes() {
+        [delimiter] if value.is_ascii() => Ok(*delimiter),
+        _ => Err(format!(
+            "The delimiter must be a single ASCII character, not {value}"
+        )),
+    }
+}
@@ -0,0 +1,22 @@
+use chrono::NaiveDate;
+
+/// Source of the current date, so that plans can be calculated as of any day.
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        return chrono::offset::Local::now().naive_utc().date();
+    }
+}
+
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        return self.0;
+    }
+}
@@ -0,0 +1,458 @@
+use std::env;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::error::KapacitetError;
+use crate::schedule::Strategy;
+use crate::scoring::Model;
+use crate::skill::SkillMatching;
+use crate::validation::Severity;
+
+pub static PROJECT_CONFIG_FILE: &str = ".kapacitet.toml";
+static USER_CONFIG_FILE: &str = "kapacitet/config.toml";
+
+static TARGET_DATE_FACTOR: f64 = 0.2;
+static DURATION_FACTOR: f64 = 0.1;
+static COMPLEXITY_FACTOR: f64 = 0.3;
+static VALUE_FACTOR: f64 = 0.4;
+
+static MAX_ESTIMATED_COMPLEXITY: f64 = 5.0;
+static MIN_ESTIMATED_COMPLEXITY: f64 = 0.0;
+
+static MAX_ESTIMATED_VALUE: f64 = 5.0;
+static MIN_ESTIMATED_VALUE: f64 = 0.0;
+
+static COMPLEXITY_FIRST_THRESHOLD: f64 = 0.3;
+static COMPLEXITY_SECOND_THRESHOLD: f64 = 0.6;
+
+static INHERITED_URGENCY: f64 = 0.5;
+
+static IMPROVEMENT_THRESHOLD: f64 = 0.1;
+
+// How far the sum of the weights may deviate from 1.0 to allow for rounding
+static WEIGHTS_SUM_TOLERANCE: f64 = 0.001;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub model: Model,
+    /// Seed for the random picks when assigning contributors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// How contributors are assigned to roadmap items
+    pub strategy: Strategy,
+    /// Whether contributors must cover the required skills of the items they are assigned to
+    pub skill_matching: SkillMatching,
+    /// Number of weeks after the as-of date to schedule roadmap items in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, message = "The horizon must be at least one week"))]
+    pub horizon_weeks: Option<u32>,
+    #[validate]
+    pub weights: Weights,
+    #[validate]
+    pub scale: Scale,
+    #[validate]
+    pub thresholds: Thresholds,
+    #[validate]
+    pub dependencies: Dependencies,
+    #[validate]
+    pub stability: Stability,
+    pub checks: Checks,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default, deny_unknown_fields)]
+#[validate(schema(function = "validate_weights"))]
+pub struct Weights {
+    #[validate(range(min = 0.0, max = 1.0, message = "Weights must be between 0 and 1"))]
+    pub target_date: f64,
+    #[validate(range(min = 0.0, max = 1.0, message = "Weights must be between 0 and 1"))]
+    pub duration: f64,
+    #[validate(range(min = 0.0, max = 1.0, message = "Weights must be between 0 and 1"))]
+    pub complexity: f64,
+    #[validate(range(min = 0.0, max = 1.0, message = "Weights must be between 0 and 1"))]
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default, deny_unknown_fields)]
+#[validate(schema(function = "validate_scale"))]
+pub struct Scale {
+    pub min_estimated_complexity: f64,
+    pub max_estimated_complexity: f64,
+    pub min_estimated_value: f64,
+    pub max_estimated_value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default, deny_unknown_fields)]
+#[validate(schema(function = "validate_thresholds"))]
+pub struct Thresholds {
+    #[validate(range(min = 0.0, max = 1.0, message = "Thresholds must be between 0 and 1"))]
+    pub first: f64,
+    #[validate(range(min = 0.0, max = 1.0, message = "Thresholds must be between 0 and 1"))]
+    pub second: f64,
+}
+
+/// Tier of urgency scores between the thresholds, from most to least urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UrgencyBand {
+    /// At or above the second threshold
+    High,
+    /// At or above the first threshold
+    Medium,
+    /// Below the first threshold
+    Low,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default, deny_unknown_fields)]
+pub struct Dependencies {
+    /// Share of an item's urgency that the items it depends on inherit
+    #[validate(range(
+        min = 0.0,
+        max = 1.0,
+        message = "The inherited urgency must be between 0 and 1"
+    ))]
+    pub inherited_urgency: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(default, deny_unknown_fields)]
+pub struct Stability {
+    /// Urgency that moving a contributor off the item they had in the previous plan has to
+    /// gain, per full-time position
+    #[validate(range(
+        min = 0.0,
+        max = 1.0,
+        message = "The improvement threshold must be between 0 and 1"
+    ))]
+    pub improvement_threshold: f64,
+}
+
+/// How severe the problems are that only show up when looking at the whole team or roadmap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Checks {
+    /// Contributors or roadmap items that share a name
+    pub duplicate_names: Severity,
+    /// Roadmap items that cannot be done by their target date even with the most capacity
+    pub unreachable_target_dates: Severity,
+    /// A team without contributors
+    pub empty_team: Severity,
+    /// A roadmap without items that start within the planning horizon
+    pub empty_horizon: Severity,
+    /// Contributors assigned to items in progress who are not on the team
+    pub unknown_contributors: Severity,
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        return Weights {
+            target_date: TARGET_DATE_FACTOR,
+            duration: DURATION_FACTOR,
+            complexity: COMPLEXITY_FACTOR,
+            value: VALUE_FACTOR,
+        };
+    }
+}
+
+impl Default for Scale {
+    fn default() -> Scale {
+        return Scale {
+            min_estimated_complexity: MIN_ESTIMATED_COMPLEXITY,
+            max_estimated_complexity: MAX_ESTIMATED_COMPLEXITY,
+            min_estimated_value: MIN_ESTIMATED_VALUE,
+            max_estimated_value: MAX_ESTIMATED_VALUE,
+        };
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Thresholds {
+        return Thresholds {
+            first: COMPLEXITY_FIRST_THRESHOLD,
+            second: COMPLEXITY_SECOND_THRESHOLD,
+        };
+    }
+}
+
+impl Thresholds {
+    pub fn band(&self, urgency: f64) -> UrgencyBand {
+        if urgency >= self.second {
+            return UrgencyBand::High;
+        } else if urgency >= self.first {
+            return UrgencyBand::Medium;
+        }
+        return UrgencyBand::Low;
+    }
+}
+
+impl Default for Dependencies {
+    fn default() -> Dependencies {
+        return Dependencies {
+            inherited_urgency: INHERITED_URGENCY,
+        };
+    }
+}
+
+impl Default for Stability {
+    fn default() -> Stability {
+        return Stability {
+            improvement_threshold: IMPROVEMENT_THRESHOLD,
+        };
+    }
+}
+
+impl Default for Checks {
+    fn default() -> Checks {
+        return Checks {
+            duplicate_names: Severity::Error,
+            unreachable_target_dates: Severity::Warning,
+            empty_team: Severity::Error,
+            empty_horizon: Severity::Warning,
+            unknown_contributors: Severity::Error,
+        };
+    }
+}
+
+impl Config {
+    /// Loads the config from `config_path` if given. Otherwise the user-level config
+    /// is loaded first and overridden by the project-level config in the current directory.
+    pub fn load(config_path: Option<&str>) -> Result<Config, KapacitetError> {
+        return Config::load_with(config_path, toml::Table::new());
+    }
+
+    /// Loads the config like `load` and overrides it with the settings of a project file.
+    pub fn load_with(
+        config_path: Option<&str>,
+        overrides: toml::Table,
+    ) -> Result<Config, KapacitetError> {
+        let config_paths: Vec<PathBuf> = match config_path {
+            Some(path) => vec![PathBuf::from(path)],
+            None => [user_config_path(), Some(PathBuf::from(PROJECT_CONFIG_FILE))]
+                .into_iter()
+                .flatten()
+                .filter(|path| path.exists())
+                .collect(),
+        };
+
+        let mut table = toml::Table::new();
+        for path in config_paths {
+            info!("Loading config from {0}", path.display());
+            merge_tables(&mut table, read_config_table(&path)?);
+        }
+        merge_tables(&mut table, overrides);
+
+        let config = Config::deserialize(toml::Value::Table(table))
+            .map_err(|err| KapacitetError::MalformedConfig(String::from("config"), err))?;
+        config.validate().map_err(KapacitetError::InvalidConfig)?;
+        return Ok(config);
+    }
+
+    /// The last day to schedule roadmap items on, if the config limits the horizon.
+    pub fn horizon_end(&self, today: NaiveDate) -> Option<NaiveDate> {
+        return self
+            .horizon_weeks
+            .map(|weeks| today + Duration::weeks(weeks as i64));
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Config, KapacitetError> {
+        let config: Config = toml::from_str(contents)
+            .map_err(|err| KapacitetError::MalformedConfig(String::from("config"), err))?;
+        config.validate().map_err(KapacitetError::InvalidConfig)?;
+        return Ok(config);
+    }
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    match env::var_os("XDG_CONFIG_HOME") {
+        Some(config_home) => {
+            return Some(Path::new(&config_home).join(USER_CONFIG_FILE));
+        }
+        None => {
+            return env::var_os("HOME")
+                .map(|home| Path::new(&home).join(".config").join(USER_CONFIG_FILE));
+        }
+    }
+}
+
+fn read_config_table(path: &Path) -> Result<toml::Table, KapacitetError> {
+    let file_path = path.display().to_string();
+    let contents = fs::read_to_string(path)
+        .map_err(|err| KapacitetError::UnreadableConfig(file_path.clone(), err))?;
+
+    // Deserialize the file on its own first so that errors point at the right file
+    toml::from_str::<Config>(contents.as_str())
+        .map_err(|err| KapacitetError::MalformedConfig(file_path.clone(), err))?;
+    return toml::from_str::<toml::Table>(contents.as_str())
+        .map_err(|err| KapacitetError::MalformedConfig(file_path, err));
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key.as_str()), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn validate_weights(weights: &Weights) -> Result<(), ValidationError> {
+    let sum = weights.target_date + weights.duration + weights.complexity + weights.value;
+    if (sum - 1.0).abs() > WEIGHTS_SUM_TOLERANCE {
+        return Err(ValidationError::new("The weights must add up to 1."));
+    }
+    return Ok(());
+}
+
+fn validate_scale(scale: &Scale) -> Result<(), ValidationError> {
+    if scale.min_estimated_complexity >= scale.max_estimated_complexity
+        || scale.min_estimated_value >= scale.max_estimated_value
+    {
+        return Err(ValidationError::new(
+            "The minimum of a scale must be below its maximum.",
+        ));
+    }
+    return Ok(());
+}
+
+impl Display for UrgencyBand {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            UrgencyBand::High => write!(f, "high"),
+            UrgencyBand::Medium => write!(f, "medium"),
+            UrgencyBand::Low => write!(f, "low"),
+        }
+    }
+}
+
+fn validate_thresholds(thresholds: &Thresholds) -> Result<(), ValidationError> {
+    if thresholds.first > thresholds.second {
+        return Err(ValidationError::new(
+            "The first threshold cannot be above the second threshold.",
+        ));
+    }
+    return Ok(());
+}
+
+impl Display for Config {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "model: {0}", self.model)?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed: {seed}")?;
+        }
+        writeln!(f, "strategy: {0}", self.strategy)?;
+        writeln!(f, "skill matching: {0}", self.skill_matching)?;
+        if let Some(weeks) = self.horizon_weeks {
+            writeln!(f, "horizon: {weeks} weeks")?;
+        }
+        writeln!(
+            f,
+            "weights: target_date={0}, duration={1}, complexity={2}, value={3}",
+            self.weights.target_date,
+            self.weights.duration,
+            self.weights.complexity,
+            self.weights.value
+        )?;
+        writeln!(
+            f,
+            "scale: estimated_complexity={0}-{1}, estimated_value={2}-{3}",
+            self.scale.min_estimated_complexity,
+            self.scale.max_estimated_complexity,
+            self.scale.min_estimated_value,
+            self.scale.max_estimated_value
+        )?;
+        writeln!(
+            f,
+            "thresholds: first={0}, second={1}",
+            self.thresholds.first, self.thresholds.second
+        )?;
+        writeln!(
+            f,
+            "dependencies: inherited_urgency={0}",
+            self.dependencies.inherited_urgency
+        )?;
+        writeln!(
+            f,
+            "stability: improvement_threshold={0}",
+            self.stability.improvement_threshold
+        )?;
+        write!(
+            f,
+            "checks: duplicate_names={0}, unreachable_target_dates={1}, empty_team={2}, empty_horizon={3}, unknown_contributors={4}",
+            self.checks.duplicate_names,
+            self.checks.unreachable_target_dates,
+            self.checks.empty_team,
+            self.checks.empty_horizon,
+            self.checks.unknown_contributors
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{merge_tables, Config};
+    use crate::error::KapacitetError;
+
+    #[test]
+    fn defaults_are_valid() {
+        assert_eq!(Config::from_toml_str("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn rejects_weights_that_do_not_add_up() {
+        assert!(matches!(
+            Config::from_toml_str("[weights]\nvalue = 0.9\n"),
+            Err(KapacitetError::InvalidConfig(_))
+        ));
+        assert!(Config::from_toml_str("[weights]\nvalue = 0.5\ncomplexity = 0.2\n").is_ok());
+    }
+
+    #[test]
+    fn records_every_section_of_a_run() {
+        let config = Config::from_toml_str(
+            "[stability]\nimprovement_threshold = 0.3\n[checks]\nempty_team = \"warning\"\n",
+        )
+        .unwrap();
+        let lines: Vec<String> = config.to_string().lines().map(String::from).collect();
+        assert_eq!(
+            &lines[lines.len() - 2..],
+            [
+                "stability: improvement_threshold=0.3",
+                "checks: duplicate_names=error, unreachable_target_dates=warning, empty_team=warning, empty_horizon=warning, unknown_contributors=error"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(matches!(
+            Config::from_toml_str("[weights]\nurgency = 0.5\n"),
+            Err(KapacitetError::MalformedConfig(_, _))
+        ));
+    }
+
+    #[test]
+    fn project_config_overrides_user_config() {
+        let mut table: toml::Table =
+            toml::from_str("[thresholds]\nfirst = 0.2\nsecond = 0.5\n").unwrap();
+        merge_tables(
+            &mut table,
+            toml::from_str("[thresholds]\nsecond = 0.7\n").unwrap(),
+        );
+        let config = Config::from_toml_str(toml::to_string(&table).unwrap().as_str()).unwrap();
+        assert_eq!(config.thresholds.first, 0.2);
+        assert_eq!(config.thresholds.second, 0.7);
+    }
+}
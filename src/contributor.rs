@@ -1,21 +1,91 @@
+use chrono::NaiveDate;
 use serde::Deserialize;
 use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::skill::{deserialize_skills, validate_skills, Skill};
+
+static FULL_TIME: f64 = 1.0;
 
 #[derive(Debug, Clone, Deserialize, Validate)]
+#[validate(schema(function = "validate_availability"))]
 pub struct Contributor {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
     #[validate(range(min = 1, max = 5, message = "Seniority must be between 1 and 5"))]
     pub seniority: usize,
+    #[serde(default, deserialize_with = "deserialize_skills")]
+    #[validate(custom = "validate_skills")]
+    pub skills: Vec<Skill>,
+    /// Share of a full-time position available for roadmap work (FTE)
+    #[serde(default = "full_time")]
+    #[validate(range(min = 0.1, max = 1.0, message = "Capacity must be between 0.1 and 1"))]
+    pub capacity: f64,
+    /// First day the contributor is available, e.g. for new hires
+    #[serde(default)]
+    pub available_from: Option<NaiveDate>,
+    /// Last day the contributor is available, e.g. for leavers
+    #[serde(default)]
+    pub available_until: Option<NaiveDate>,
+    /// Share of a full-time position allocated to a roadmap item once assigned to it
+    #[serde(skip)]
+    pub allocation: Option<f64>,
 }
 
 impl Contributor {
     pub fn new(name: String, seniority: usize) -> Contributor {
-        return Contributor { name, seniority };
+        return Contributor {
+            name,
+            seniority,
+            skills: Vec::new(),
+            capacity: FULL_TIME,
+            available_from: None,
+            available_until: None,
+            allocation: None,
+        };
+    }
+
+    /// Takes the given share of the contributor's capacity for a roadmap item.
+    pub fn allocate(&self, allocation: f64) -> Contributor {
+        let mut contributor = self.clone();
+        contributor.allocation = Some(allocation);
+        return contributor;
+    }
+
+    pub fn get_allocation(&self) -> f64 {
+        self.allocation.unwrap_or(self.capacity)
+    }
+
+    /// Whether the date is within the contributor's availability window.
+    pub fn is_employed_on(&self, date: NaiveDate) -> bool {
+        return self.available_from.is_none_or(|from| from <= date)
+            && self.available_until.is_none_or(|until| date <= until);
+    }
+
+    /// Whether the contributor has the skill at the given level or above.
+    pub fn has_skill(&self, skill: &Skill) -> bool {
+        return self
+            .skills
+            .iter()
+            .any(|own| own.name == skill.name && own.level >= skill.level);
+    }
+}
+
+fn full_time() -> f64 {
+    return FULL_TIME;
+}
+
+fn validate_availability(contributor: &Contributor) -> Result<(), ValidationError> {
+    if let (Some(from), Some(until)) = (contributor.available_from, contributor.available_until) {
+        if until < from {
+            return Err(ValidationError::new(
+                "The contributor cannot be available until before they are available from.",
+            ));
+        }
     }
+    return Ok(());
 }
 
 impl Ord for Contributor {
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+
+use serde::de::{SeqAccess, Visitor};
+use serde::Deserializer;
+
+use crate::error::KapacitetError;
+use crate::roadmap::RoadmapItem;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    Pending,
+    InProgress,
+    Done,
+}
+
+/// Checks that the dependencies name existing roadmap items and don't form a cycle. Returns
+/// the indices of the items ordered so that every item comes after the items it depends on.
+pub fn topological_order(roadmap_items: &[RoadmapItem]) -> Result<Vec<usize>, KapacitetError> {
+    let index: HashMap<&str, usize> = roadmap_items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.name.as_str(), i))
+        .collect();
+    let mut visits: Vec<Visit> = vec![Visit::Pending; roadmap_items.len()];
+    let mut path: Vec<usize> = Vec::new();
+    let mut order: Vec<usize> = Vec::new();
+    for i in 0..roadmap_items.len() {
+        visit(i, roadmap_items, &index, &mut visits, &mut path, &mut order)?;
+    }
+    return Ok(order);
+}
+
+fn visit(
+    i: usize,
+    roadmap_items: &[RoadmapItem],
+    index: &HashMap<&str, usize>,
+    visits: &mut Vec<Visit>,
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+) -> Result<(), KapacitetError> {
+    match visits[i] {
+        Visit::Done => {
+            return Ok(());
+        }
+        Visit::InProgress => {
+            let start = path.iter().position(|j| *j == i).unwrap_or(0);
+            let mut cycle: Vec<String> = path[start..]
+                .iter()
+                .map(|j| roadmap_items[*j].name.clone())
+                .collect();
+            cycle.push(roadmap_items[i].name.clone());
+            return Err(KapacitetError::DependencyCycle(cycle));
+        }
+        Visit::Pending => {}
+    }
+
+    visits[i] = Visit::InProgress;
+    path.push(i);
+    for name in &roadmap_items[i].depends_on {
+        match index.get(name.as_str()) {
+            None => {
+                return Err(KapacitetError::UnknownDependency(
+                    roadmap_items[i].name.clone(),
+                    name.clone(),
+                ));
+            }
+            Some(j) => visit(*j, roadmap_items, index, visits, path, order)?,
+        }
+    }
+    path.pop();
+    visits[i] = Visit::Done;
+    order.push(i);
+    return Ok(());
+}
+
+/// Lets the items that others depend on inherit a share of the urgency of the items waiting
+/// on them, so that blockers are staffed first.
+pub fn propagate_urgency(
+    roadmap_items: &mut [RoadmapItem],
+    share: f64,
+) -> Result<(), KapacitetError> {
+    let order = topological_order(roadmap_items)?;
+    let index: HashMap<String, usize> = roadmap_items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.name.clone(), i))
+        .collect();
+    // Items waiting on others come last in the order, so their urgency is passed on first
+    for i in order.into_iter().rev() {
+        let inherited = (roadmap_items[i].get_urgency() * share * 100.0).round() / 100.0;
+        let name = roadmap_items[i].name.clone();
+        for dependency in roadmap_items[i].depends_on.clone() {
+            let blocker = &mut roadmap_items[index[&dependency]];
+            if inherited > blocker.get_urgency() {
+                debug!(
+                    "Roadmap item {0} inherits urgency {inherited} from {name}",
+                    blocker.name
+                );
+                blocker.inherit_urgency(inherited, name.as_str());
+            }
+        }
+    }
+    return Ok(());
+}
+
+/// Deserializes item names from `;`-separated text or a list of names.
+pub fn deserialize_names<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    return deserializer.deserialize_any(NamesVisitor);
+}
+
+pub fn parse_names(text: &str) -> Vec<String> {
+    return text
+        .split(';')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+}
+
+struct NamesVisitor;
+
+impl<'de> Visitor<'de> for NamesVisitor {
+    type Value = Vec<String>;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "roadmap item names such as \"Auth;Billing\"")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Vec<String>, E> {
+        return Ok(parse_names(value));
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Vec<String>, E> {
+        return Ok(Vec::new());
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Vec<String>, E> {
+        return Ok(Vec::new());
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<String>, A::Error> {
+        let mut names: Vec<String> = Vec::new();
+        while let Some(name) = seq.next_element::<String>()? {
+            names.push(name);
+        }
+        return Ok(names);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::dependency::{propagate_urgency, topological_order};
+    use crate::error::KapacitetError;
+    use crate::roadmap::RoadmapItem;
+
+    fn item(name: &str, urgency: f64, depends_on: &[&str]) -> RoadmapItem {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let mut item = RoadmapItem::new(
+            String::from(name),
+            1,
+            1,
+            today,
+            today + Duration::days(365),
+            vec![],
+        );
+        item.urgency = Some(urgency);
+        item.depends_on = depends_on.iter().map(|name| String::from(*name)).collect();
+        return item;
+    }
+
+    #[test]
+    fn orders_blockers_first() {
+        let items = vec![
+            item("payments", 0.8, &["auth"]),
+            item("auth", 0.2, &["infra"]),
+            item("infra", 0.1, &[]),
+        ];
+        assert_eq!(topological_order(&items).unwrap(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn rejects_cycles_and_unknown_items() {
+        let items = vec![
+            item("payments", 0.8, &["auth"]),
+            item("auth", 0.2, &["payments"]),
+        ];
+        assert!(matches!(
+            topological_order(&items),
+            Err(KapacitetError::DependencyCycle(cycle)) if cycle == vec!["payments", "auth", "payments"]
+        ));
+        assert!(matches!(
+            topological_order(&[item("payments", 0.8, &["billing"])]),
+            Err(KapacitetError::UnknownDependency(item, dependency)) if item == "payments" && dependency == "billing"
+        ));
+    }
+
+    #[test]
+    fn blockers_inherit_urgency() {
+        let mut items = vec![
+            item("payments", 0.8, &["auth"]),
+            item("auth", 0.2, &["infra"]),
+            item("infra", 0.3, &[]),
+        ];
+        propagate_urgency(&mut items, 0.5).unwrap();
+        assert_eq!(items[0].get_urgency(), 0.8);
+        assert_eq!(items[1].get_urgency(), 0.4);
+        assert_eq!(items[2].get_urgency(), 0.3);
+    }
+}
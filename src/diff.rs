@@ -0,0 +1,362 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+use crate::report::ItemReport;
+
+/// What changed between two plans, with items ranked from most to least urgent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanDiff {
+    pub added: Vec<ItemDiff>,
+    pub removed: Vec<ItemDiff>,
+    /// Items on both plans whose rank or urgency changed
+    pub changed: Vec<ItemDiff>,
+    pub moves: Vec<Move>,
+    pub reallocations: Vec<Reallocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemDiff {
+    pub name: String,
+    /// Position from 1 in the old plan
+    pub old_rank: Option<usize>,
+    /// Position from 1 in the new plan
+    pub new_rank: Option<usize>,
+    pub old_urgency: Option<f64>,
+    pub new_urgency: Option<f64>,
+    pub urgency_delta: Option<f64>,
+}
+
+/// A contributor who left or joined items between the plans.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Move {
+    pub contributor: String,
+    pub left: Vec<String>,
+    pub joined: Vec<String>,
+}
+
+/// A contributor who stayed on an item with a different share of their capacity.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reallocation {
+    pub contributor: String,
+    pub item: String,
+    pub old_allocation: f64,
+    pub new_allocation: f64,
+}
+
+impl PlanDiff {
+    pub fn new(old_plan: &[ItemReport], new_plan: &[ItemReport]) -> PlanDiff {
+        let rank = |plan: &[ItemReport], name: &str| {
+            return plan.iter().position(|item| item.name == name);
+        };
+        let item_diff = |name: &str| {
+            let old = rank(old_plan, name);
+            let new = rank(new_plan, name);
+            let old_urgency = old.map(|i| old_plan[i].urgency);
+            let new_urgency = new.map(|i| new_plan[i].urgency);
+            return ItemDiff {
+                name: String::from(name),
+                old_rank: old.map(|i| i + 1),
+                new_rank: new.map(|i| i + 1),
+                old_urgency,
+                new_urgency,
+                urgency_delta: old_urgency
+                    .zip(new_urgency)
+                    .map(|(old, new)| round(new - old)),
+            };
+        };
+
+        let mut diff = PlanDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+            moves: Vec::new(),
+            reallocations: Vec::new(),
+        };
+        for item in new_plan {
+            let item_diff = item_diff(item.name.as_str());
+            if item_diff.old_rank.is_none() {
+                diff.added.push(item_diff);
+            } else if item_diff.old_rank != item_diff.new_rank
+                || item_diff.urgency_delta.is_some_and(|delta| delta != 0.0)
+            {
+                diff.changed.push(item_diff);
+            }
+        }
+        for item in old_plan {
+            if rank(new_plan, item.name.as_str()).is_none() {
+                diff.removed.push(item_diff(item.name.as_str()));
+            }
+        }
+
+        let mut contributors: Vec<&str> = old_plan
+            .iter()
+            .chain(new_plan)
+            .flat_map(|item| item.contributors.iter())
+            .map(|contributor| contributor.name.as_str())
+            .collect();
+        contributors.sort();
+        contributors.dedup();
+        for contributor in contributors {
+            let items = |plan: &[ItemReport]| {
+                return plan
+                    .iter()
+                    .filter(|item| item.contributors.iter().any(|c| c.name == contributor))
+                    .map(|item| item.name.clone())
+                    .collect::<Vec<String>>();
+            };
+            let old_items = items(old_plan);
+            let new_items = items(new_plan);
+            let left: Vec<String> = old_items
+                .iter()
+                .filter(|name| !new_items.contains(name))
+                .cloned()
+                .collect();
+            let joined: Vec<String> = new_items
+                .iter()
+                .filter(|name| !old_items.contains(name))
+                .cloned()
+                .collect();
+            if !left.is_empty() || !joined.is_empty() {
+                diff.moves.push(Move {
+                    contributor: String::from(contributor),
+                    left,
+                    joined,
+                });
+            }
+        }
+
+        for new_item in new_plan {
+            let Some(old_item) = old_plan.iter().find(|item| item.name == new_item.name) else {
+                continue;
+            };
+            for new in &new_item.contributors {
+                let old = old_item
+                    .contributors
+                    .iter()
+                    .find(|old| old.name == new.name);
+                if let Some(old) = old.filter(|old| round(old.allocation - new.allocation) != 0.0) {
+                    diff.reallocations.push(Reallocation {
+                        contributor: new.name.clone(),
+                        item: new_item.name.clone(),
+                        old_allocation: old.allocation,
+                        new_allocation: new.allocation,
+                    });
+                }
+            }
+        }
+        return diff;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.moves.is_empty()
+            && self.reallocations.is_empty();
+    }
+
+    /// Renders the diff as GitHub-flavored Markdown tables to paste into planning docs.
+    pub fn to_markdown(&self) -> String {
+        if self.is_empty() {
+            return String::from("No changes between the plans.\n");
+        }
+        let mut markdown = String::new();
+        let items = [
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("changed", &self.changed),
+        ];
+        if items.iter().any(|(_, diffs)| !diffs.is_empty()) {
+            markdown.push_str("| Item | Change | Rank | Urgency |\n|---|---|---|---|\n");
+            for (change, diffs) in items {
+                for diff in diffs {
+                    markdown.push_str(&format!(
+                        "| {0} | {change} | {1} | {2} |\n",
+                        escape(&diff.name),
+                        diff.rank_change(),
+                        diff.urgency_change()
+                    ));
+                }
+            }
+        }
+        if !self.moves.is_empty() {
+            if !markdown.is_empty() {
+                markdown.push('\n');
+            }
+            markdown.push_str("| Contributor | Left | Joined |\n|---|---|---|\n");
+            for diff in &self.moves {
+                markdown.push_str(&format!(
+                    "| {0} | {1} | {2} |\n",
+                    escape(&diff.contributor),
+                    escape(&diff.left.join(", ")),
+                    escape(&diff.joined.join(", "))
+                ));
+            }
+        }
+        if !self.reallocations.is_empty() {
+            if !markdown.is_empty() {
+                markdown.push('\n');
+            }
+            markdown.push_str("| Contributor | Item | Allocation |\n|---|---|---|\n");
+            for diff in &self.reallocations {
+                markdown.push_str(&format!(
+                    "| {0} | {1} | {2} |\n",
+                    escape(&diff.contributor),
+                    escape(&diff.item),
+                    diff.allocation_change()
+                ));
+            }
+        }
+        return markdown;
+    }
+}
+
+impl ItemDiff {
+    fn rank_change(&self) -> String {
+        match (self.old_rank, self.new_rank) {
+            (Some(old), Some(new)) if old != new => format!("{old} -> {new}"),
+            (Some(rank), _) | (None, Some(rank)) => format!("{rank}"),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn urgency_change(&self) -> String {
+        match (self.old_urgency, self.new_urgency, self.urgency_delta) {
+            (Some(old), Some(new), Some(delta)) if delta != 0.0 => {
+                format!("{old} -> {new} ({delta:+})")
+            }
+            (Some(urgency), _, _) | (None, Some(urgency), _) => format!("{urgency}"),
+            (None, None, _) => String::new(),
+        }
+    }
+}
+
+impl Reallocation {
+    fn allocation_change(&self) -> String {
+        return format!(
+            "{0}% -> {1}%",
+            (self.old_allocation * 100.0).round(),
+            (self.new_allocation * 100.0).round()
+        );
+    }
+}
+
+// Urgencies have two decimals, which subtracting shouldn't turn into float noise
+fn round(value: f64) -> f64 {
+    return (value * 100.0).round() / 100.0;
+}
+
+fn escape(text: &str) -> String {
+    return text.replace('|', "\\|").replace('\n', " ");
+}
+
+impl Display for PlanDiff {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes between the plans");
+        }
+        for (title, diffs) in [
+            ("Added", &self.added),
+            ("Removed", &self.removed),
+            ("Changed", &self.changed),
+        ] {
+            if diffs.is_empty() {
+                continue;
+            }
+            writeln!(f, "{title}:")?;
+            for diff in diffs {
+                writeln!(
+                    f,
+                    "  {0}: rank {1}, urgency {2}",
+                    diff.name,
+                    diff.rank_change(),
+                    diff.urgency_change()
+                )?;
+            }
+        }
+        if !self.moves.is_empty() {
+            writeln!(f, "Moves:")?;
+            for diff in &self.moves {
+                write!(f, "  {0}:", diff.contributor)?;
+                if !diff.left.is_empty() {
+                    write!(f, " left {0}", diff.left.join(", "))?;
+                }
+                if !diff.left.is_empty() && !diff.joined.is_empty() {
+                    write!(f, ",")?;
+                }
+                if !diff.joined.is_empty() {
+                    write!(f, " joined {0}", diff.joined.join(", "))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        if !self.reallocations.is_empty() {
+            writeln!(f, "Reallocations:")?;
+            for diff in &self.reallocations {
+                writeln!(
+                    f,
+                    "  {0} on {1}: {2}",
+                    diff.contributor,
+                    diff.item,
+                    diff.allocation_change()
+                )?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::PlanDiff;
+    use crate::input::read_plan;
+
+    #[test]
+    fn reports_added_removed_and_changed_items_and_moves() {
+        let old_plan = read_plan(
+            "name,start date,target date,urgency (0-1),contributors,scheduled start,scheduled end,skill gaps\n\
+             MVP,2022-06-01,2022-11-01,0.65,Ada (50%);Bob (100%),2022-10-17,2022-11-01,\n\
+             Docs,2022-06-01,2022-12-01,0.3,Cyd (100%),2022-10-17,2022-10-28,\n\
+             Auth,2022-06-01,2022-12-01,0.2,,,,\n"
+                .as_bytes(),
+            b',',
+        )
+        .unwrap();
+        let new_plan = read_plan(
+            "name,start date,target date,urgency (0-1),contributors,scheduled start,scheduled end,skill gaps\n\
+             Auth,2022-06-01,2022-12-01,0.7,Cyd (100%),2022-10-17,2022-11-01,\n\
+             MVP,2022-06-01,2022-11-01,0.65,Ada (50%);Bob (25%),2022-10-17,2022-11-01,\n\
+             Billing,2022-06-01,2023-01-01,0.4,,,,\n"
+                .as_bytes(),
+            b',',
+        )
+        .unwrap();
+
+        let diff = PlanDiff::new(&old_plan, &new_plan);
+        assert_eq!(diff.added[0].name, "Billing");
+        assert_eq!(diff.removed[0].name, "Docs");
+        assert_eq!(diff.changed.len(), 2);
+        assert_eq!(diff.changed[0].urgency_delta, Some(0.5));
+        assert_eq!(diff.changed[1].name, "MVP");
+        assert_eq!(
+            diff.to_string(),
+            "Added:\n  Billing: rank 3, urgency 0.4\n\
+             Removed:\n  Docs: rank 2, urgency 0.3\n\
+             Changed:\n  Auth: rank 3 -> 1, urgency 0.2 -> 0.7 (+0.5)\n  MVP: rank 1 -> 2, urgency 0.65\n\
+             Moves:\n  Cyd: left Docs, joined Auth\n\
+             Reallocations:\n  Bob on MVP: 100% -> 25%\n"
+        );
+        assert!(diff
+            .to_markdown()
+            .contains("| Auth | changed | 3 -> 1 | 0.2 -> 0.7 (+0.5) |\n"));
+        assert!(diff.to_markdown().ends_with(
+            "| Contributor | Item | Allocation |\n|---|---|---|\n| Bob | MVP | 100% -> 25% |\n"
+        ));
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap()["reallocations"][0]["new_allocation"],
+            serde_json::json!(0.25)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+use validator::ValidationErrors;
+
+#[derive(Debug)]
+pub enum KapacitetError {
+    UnreadableFile(String, csv::Error),
+    MalformedContributor(csv::Error),
+    MalformedRoadmapItem(csv::Error),
+    InvalidContributor(String, ValidationErrors),
+    InvalidRoadmapItem(String, ValidationErrors),
+    UnreadableConfig(String, io::Error),
+    MalformedConfig(String, toml::de::Error),
+    InvalidConfig(ValidationErrors),
+    MissingScoringField(String, &'static str),
+    UnknownDependency(String, String),
+    UnreadableCalendar(String, io::Error),
+    MalformedCalendar(String, String),
+    MalformedAbsence(csv::Error),
+    InvalidAbsence(String, ValidationErrors),
+    DependencyCycle(Vec<String>),
+    UnwritableOutput(csv::Error),
+    MalformedPlan(String),
+    MalformedInput(String, String),
+}
+
+impl Display for KapacitetError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            KapacitetError::UnreadableFile(file_path, err) => write!(
+                f,
+                "Unable to read {file_path}. Make sure that it has the right format! {err}"
+            ),
+            KapacitetError::MalformedContributor(err) => write!(
+                f,
+                "Malformed contributor. Make sure that all contributors have the right format! {err}"
+            ),
+            KapacitetError::MalformedRoadmapItem(err) => write!(
+                f,
+                "Malformed roadmap item. Make sure that all roadmap items have the right format! {err}"
+            ),
+            KapacitetError::InvalidContributor(name, errors) => write!(
+                f,
+                "Invalid contributor {name}. Make sure that all contributors have valid values! {errors}"
+            ),
+            KapacitetError::InvalidRoadmapItem(name, errors) => write!(
+                f,
+                "Invalid roadmap item {name}. Make sure that all roadmap items have valid values! {errors}"
+            ),
+            KapacitetError::UnreadableConfig(file_path, err) => {
+                write!(f, "Unable to read config file {file_path}. {err}")
+            }
+            KapacitetError::MalformedConfig(file_path, err) => write!(
+                f,
+                "Malformed config in {file_path}. Make sure that it has the right format! {err}"
+            ),
+            KapacitetError::InvalidConfig(errors) => write!(
+                f,
+                "Invalid config. Make sure that the weights, scale and thresholds are valid! {errors}"
+            ),
+            KapacitetError::MissingScoringField(name, field) => write!(
+                f,
+                "Roadmap item {name} is missing {field}. Make sure that all roadmap items have the fields the scoring model needs!"
+            ),
+            KapacitetError::UnknownDependency(name, dependency) => write!(
+                f,
+                "Roadmap item {name} depends on {dependency}, which is not on the roadmap. Make sure that dependencies name existing roadmap items!"
+            ),
+            KapacitetError::UnreadableCalendar(file_path, err) => {
+                write!(f, "Unable to read calendar file {file_path}. {err}")
+            }
+            KapacitetError::MalformedCalendar(file_path, reason) => write!(
+                f,
+                "Malformed calendar in {file_path}. Make sure that it is a valid iCalendar file! {reason}"
+            ),
+            KapacitetError::MalformedAbsence(err) => write!(
+                f,
+                "Malformed calendar entry. Make sure that all holidays and time off have the right format! {err}"
+            ),
+            KapacitetError::InvalidAbsence(description, errors) => write!(
+                f,
+                "Invalid calendar entry {description}. Make sure that all holidays and time off have valid dates! {errors}"
+            ),
+            KapacitetError::DependencyCycle(names) => write!(
+                f,
+                "Roadmap items depend on each other in a cycle: {0}. Remove one of the dependencies!",
+                names.join(" -> ")
+            ),
+            KapacitetError::UnwritableOutput(err) => write!(f, "Unable to write the output. {err}"),
+            KapacitetError::MalformedInput(source, reason) => write!(
+                f,
+                "Malformed {source}. Make sure that it has the right format! {reason}"
+            ),
+            KapacitetError::MalformedPlan(reason) => write!(
+                f,
+                "Malformed plan. Make sure that it is the CSV or JSON output of kapacitet plan! {reason}"
+            ),
+        }
+    }
+}
+
+impl Error for KapacitetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KapacitetError::UnreadableFile(_, err) => Some(err),
+            KapacitetError::MalformedContributor(err) => Some(err),
+            KapacitetError::MalformedRoadmapItem(err) => Some(err),
+            KapacitetError::InvalidContributor(_, errors) => Some(errors),
+            KapacitetError::InvalidRoadmapItem(_, errors) => Some(errors),
+            KapacitetError::UnreadableConfig(_, err) => Some(err),
+            KapacitetError::MalformedConfig(_, err) => Some(err),
+            KapacitetError::InvalidConfig(errors) => Some(errors),
+            KapacitetError::MissingScoringField(_, _) => None,
+            KapacitetError::UnknownDependency(_, _) => None,
+            KapacitetError::DependencyCycle(_) => None,
+            KapacitetError::UnreadableCalendar(_, err) => Some(err),
+            KapacitetError::MalformedCalendar(_, _) => None,
+            KapacitetError::MalformedAbsence(err) => Some(err),
+            KapacitetError::InvalidAbsence(_, errors) => Some(errors),
+            KapacitetError::UnwritableOutput(err) => Some(err),
+            KapacitetError::MalformedPlan(_) => None,
+            KapacitetError::MalformedInput(_, _) => None,
+        }
+    }
+}
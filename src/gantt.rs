@@ -0,0 +1,525 @@
+use std::io::Write;
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+use crate::config::{Thresholds, UrgencyBand};
+use crate::error::KapacitetError;
+use crate::report::{ItemReport, PlanReport};
+
+static LABEL_WIDTH: f64 = 200.0;
+static CHART_WIDTH: f64 = 760.0;
+// Room right of the chart for the labels of bars that end late
+static LABEL_MARGIN: f64 = 180.0;
+static ROW_HEIGHT: f64 = 28.0;
+static BAR_HEIGHT: f64 = 18.0;
+static HEADER_HEIGHT: f64 = 64.0;
+static SECTION_HEIGHT: f64 = 36.0;
+static MIN_DAYS: i64 = 14;
+static MAX_LABEL_CHARS: usize = 28;
+
+static BANDS: [UrgencyBand; 3] = [UrgencyBand::High, UrgencyBand::Medium, UrgencyBand::Low];
+
+static STYLE: &str = "text { font-family: sans-serif; font-size: 12px; fill: #222; } \
+    .section { font-size: 14px; font-weight: bold; } \
+    .month { fill: #666; } \
+    .grid { stroke: #e4e4e4; } \
+    .lane { fill: #eee; fill-opacity: 0.5; } \
+    .unscheduled { fill-opacity: 0.35; stroke-dasharray: 4 2; } \
+    .target { stroke: #222; stroke-width: 2; } \
+    .today { stroke: #c00; stroke-width: 1.5; stroke-dasharray: 6 3; } \
+    .today-label { fill: #c00; }";
+
+struct Bar<'a> {
+    item: &'a ItemReport,
+    start: NaiveDate,
+    /// Last day of the bar
+    end: NaiveDate,
+    scheduled: bool,
+}
+
+struct Timeline {
+    first: NaiveDate,
+    days: i64,
+}
+
+impl PlanReport {
+    /// Writes the plan as an SVG Gantt chart: one bar per roadmap item, coloured by urgency
+    /// band and labelled with its contributors, then one swimlane per contributor.
+    pub fn write_svg<W: Write>(&self, mut writer: W) -> Result<(), KapacitetError> {
+        return writer
+            .write_all(self.to_svg().as_bytes())
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    /// Writes the plan as a self-contained HTML page with the Gantt chart of `write_svg`.
+    pub fn write_html<W: Write>(&self, mut writer: W) -> Result<(), KapacitetError> {
+        let metadata = &self.metadata;
+        let mut details = vec![
+            format!("model: {0}", metadata.model),
+            format!("strategy: {0}", metadata.strategy),
+        ];
+        if let Some(seed) = metadata.seed {
+            details.push(format!("seed: {seed}"));
+        }
+        let html = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Plan as of {0}</title>\n\
+             <style>body {{ font-family: sans-serif; margin: 24px; color: #222; }} \
+             p {{ color: #666; }}</style>\n</head>\n<body>\n<h1>Plan as of {0}</h1>\n\
+             <p>{1}</p>\n{2}</body>\n</html>\n",
+            metadata.as_of,
+            escape(&details.join(", ")),
+            self.to_svg()
+        );
+        return writer
+            .write_all(html.as_bytes())
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    /// Writes the plan as a Mermaid Gantt diagram with a section per urgency band and the
+    /// contributors in the task labels.
+    pub fn write_mermaid<W: Write>(&self, mut writer: W) -> Result<(), KapacitetError> {
+        let mut mermaid = format!(
+            "gantt\n    title Plan as of {0}\n    dateFormat YYYY-MM-DD\n    axisFormat %b %d\n",
+            self.metadata.as_of
+        );
+        for (band, bars) in self.bands() {
+            mermaid.push_str(&format!(
+                "    section Urgency {0}\n",
+                band_label(band, &self.metadata.thresholds)
+            ));
+            for bar in bars {
+                // Mermaid ends tasks at the start of the end date
+                mermaid.push_str(&format!(
+                    "    {0} :{1}, {2}\n",
+                    mermaid_text(&task_label(bar.item)),
+                    bar.start,
+                    bar.end + Duration::days(1)
+                ));
+            }
+        }
+        return writer
+            .write_all(mermaid.as_bytes())
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    /// Writes the plan as a PlantUML Gantt diagram with a separator per urgency band, the
+    /// contributors in the task labels and today marked.
+    pub fn write_plantuml<W: Write>(&self, mut writer: W) -> Result<(), KapacitetError> {
+        let bars: Vec<Bar> = self.items.iter().map(Bar::from).collect();
+        let timeline = Timeline::new(&bars, self.metadata.as_of);
+        let mut plantuml = format!(
+            "@startgantt\ntitle Plan as of {0}\nProject starts {1}\n\
+             today is {2} days after start and is colored in #fcc\n",
+            self.metadata.as_of,
+            timeline.first,
+            (self.metadata.as_of - timeline.first).num_days()
+        );
+        for (band, bars) in self.bands() {
+            plantuml.push_str(&format!(
+                "-- Urgency {0} --\n",
+                band_label(band, &self.metadata.thresholds)
+            ));
+            for bar in bars {
+                let task = plantuml_text(&task_label(bar.item));
+                plantuml.push_str(&format!(
+                    "[{task}] starts {0} and ends {1}\n[{task}] is colored in {2}\n",
+                    bar.start,
+                    bar.end,
+                    colour(self.metadata.thresholds.band(bar.item.urgency))
+                ));
+            }
+        }
+        plantuml.push_str("@endgantt\n");
+        return writer
+            .write_all(plantuml.as_bytes())
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    // The bars of the items in each urgency band that has any, from most to least urgent
+    fn bands(&self) -> Vec<(UrgencyBand, Vec<Bar<'_>>)> {
+        return BANDS
+            .iter()
+            .map(|band| {
+                let bars = self
+                    .items
+                    .iter()
+                    .filter(|item| self.metadata.thresholds.band(item.urgency) == *band)
+                    .map(Bar::from)
+                    .collect::<Vec<Bar>>();
+                return (*band, bars);
+            })
+            .filter(|(_, bars)| !bars.is_empty())
+            .collect();
+    }
+
+    fn to_svg(&self) -> String {
+        let bars: Vec<Bar> = self.items.iter().map(Bar::from).collect();
+        let timeline = Timeline::new(&bars, self.metadata.as_of);
+        let lanes = self.swimlanes(&bars);
+        let lane_rows: usize = lanes.iter().map(|(_, rows)| rows.len().max(1)).sum();
+        let item_top = HEADER_HEIGHT + SECTION_HEIGHT;
+        let lane_top = item_top + bars.len() as f64 * ROW_HEIGHT + SECTION_HEIGHT;
+        let height = lane_top + lane_rows as f64 * ROW_HEIGHT + 8.0;
+        let width = LABEL_WIDTH + CHART_WIDTH + LABEL_MARGIN;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\">\n<style>{STYLE}</style>\n"
+        );
+        svg.push_str(&legend(&self.metadata.thresholds));
+        svg.push_str(&timeline.months(height));
+
+        svg.push_str(&section("Roadmap items", item_top));
+        for (row, bar) in bars.iter().enumerate() {
+            let y = item_top + row as f64 * ROW_HEIGHT;
+            svg.push_str(&row_label(&bar.item.name, y));
+            svg.push_str(&timeline.bar(bar, y, &staffing(bar.item), &self.metadata.thresholds));
+            let target = timeline.x(bar.item.target_date + Duration::days(1));
+            svg.push_str(&format!(
+                "<line class=\"target\" x1=\"{target:.1}\" y1=\"{0:.1}\" x2=\"{target:.1}\" \
+                 y2=\"{1:.1}\"><title>Target date {2}</title></line>\n",
+                y + 2.0,
+                y + ROW_HEIGHT - 2.0,
+                bar.item.target_date
+            ));
+        }
+
+        svg.push_str(&section("Contributors", lane_top));
+        let mut y = lane_top;
+        for (name, rows) in &lanes {
+            let lane_height = rows.len().max(1) as f64 * ROW_HEIGHT;
+            svg.push_str(&format!(
+                "<rect class=\"lane\" x=\"0\" y=\"{y:.1}\" width=\"{0:.1}\" \
+                 height=\"{1:.1}\"/>\n",
+                LABEL_WIDTH + CHART_WIDTH,
+                lane_height - 2.0
+            ));
+            svg.push_str(&row_label(name, y));
+            if rows.is_empty() {
+                svg.push_str(&format!(
+                    "<text class=\"month\" x=\"{0:.1}\" y=\"{1:.1}\">idle</text>\n",
+                    LABEL_WIDTH + 4.0,
+                    y + ROW_HEIGHT / 2.0 + 4.0
+                ));
+            }
+            for (row, row_bars) in rows.iter().enumerate() {
+                for bar in row_bars {
+                    let label = match bar.item.contributors.iter().find(|c| &c.name == name) {
+                        Some(contributor) => format!(
+                            "{0} ({1}%)",
+                            bar.item.name,
+                            (contributor.allocation * 100.0).round()
+                        ),
+                        None => bar.item.name.clone(),
+                    };
+                    svg.push_str(&timeline.bar(
+                        bar,
+                        y + row as f64 * ROW_HEIGHT,
+                        &label,
+                        &self.metadata.thresholds,
+                    ));
+                }
+            }
+            y += lane_height;
+        }
+
+        let today = timeline.x(self.metadata.as_of);
+        svg.push_str(&format!(
+            "<line class=\"today\" x1=\"{today:.1}\" y1=\"{0:.1}\" x2=\"{today:.1}\" \
+             y2=\"{height}\"/>\n<text class=\"today-label\" x=\"{1:.1}\" y=\"{2:.1}\">\
+             today</text>\n</svg>\n",
+            HEADER_HEIGHT - 16.0,
+            today + 4.0,
+            HEADER_HEIGHT - 4.0
+        ));
+        return svg;
+    }
+
+    // Every contributor with the bars of their items, stacked in rows so that they don't
+    // overlap. Contributors without items get a lane without rows.
+    fn swimlanes<'a>(&self, bars: &'a [Bar<'a>]) -> Vec<(String, Vec<Vec<&'a Bar<'a>>>)> {
+        let mut names: Vec<&str> = self
+            .items
+            .iter()
+            .flat_map(|item| item.contributors.iter())
+            .chain(self.unassigned_contributors.iter())
+            .map(|contributor| contributor.name.as_str())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut lanes = Vec::new();
+        for name in names {
+            let mut own: Vec<&Bar> = bars
+                .iter()
+                .filter(|bar| bar.item.contributors.iter().any(|c| c.name == name))
+                .collect();
+            own.sort_by_key(|bar| bar.start);
+            let mut rows: Vec<Vec<&Bar>> = Vec::new();
+            for bar in own {
+                match rows
+                    .iter_mut()
+                    .find(|row| row.last().is_some_and(|last| last.end < bar.start))
+                {
+                    Some(row) => row.push(bar),
+                    None => rows.push(vec![bar]),
+                }
+            }
+            lanes.push((String::from(name), rows));
+        }
+        return lanes;
+    }
+}
+
+impl<'a> From<&'a ItemReport> for Bar<'a> {
+    fn from(item: &'a ItemReport) -> Bar<'a> {
+        match (item.scheduled_start, item.scheduled_end) {
+            (Some(start), Some(end)) => Bar {
+                item,
+                start,
+                end,
+                scheduled: true,
+            },
+            _ => Bar {
+                item,
+                start: item.start_date,
+                end: item.target_date,
+                scheduled: false,
+            },
+        }
+    }
+}
+
+impl Timeline {
+    /// Spans every bar, target date and today, and at least `MIN_DAYS`.
+    fn new(bars: &[Bar], today: NaiveDate) -> Timeline {
+        let first = bars.iter().map(|bar| bar.start).fold(today, NaiveDate::min);
+        let last = bars
+            .iter()
+            .flat_map(|bar| [bar.end, bar.item.target_date])
+            .fold(today, NaiveDate::max);
+        return Timeline {
+            first,
+            days: ((last - first).num_days() + 1).max(MIN_DAYS),
+        };
+    }
+
+    /// Position of the start of the day.
+    fn x(&self, date: NaiveDate) -> f64 {
+        return LABEL_WIDTH
+            + (date - self.first).num_days() as f64 * CHART_WIDTH / self.days as f64;
+    }
+
+    fn bar(&self, bar: &Bar, y: f64, label: &str, thresholds: &Thresholds) -> String {
+        let start = self.x(bar.start);
+        let end = self.x(bar.end + Duration::days(1));
+        let band = thresholds.band(bar.item.urgency);
+        let (class, dates) = if bar.scheduled {
+            ("bar", format!("scheduled {0} to {1}", bar.start, bar.end))
+        } else {
+            (
+                "bar unscheduled",
+                format!("not scheduled, {0} to {1}", bar.start, bar.end),
+            )
+        };
+        return format!(
+            "<rect class=\"{class}\" x=\"{start:.1}\" y=\"{0:.1}\" width=\"{1:.1}\" \
+             height=\"{BAR_HEIGHT}\" rx=\"3\" fill=\"{2}\" stroke=\"{2}\"><title>{3}: {4} \
+             urgency {5}, {dates}</title></rect>\n\
+             <text x=\"{6:.1}\" y=\"{7:.1}\">{8}</text>\n",
+            y + (ROW_HEIGHT - BAR_HEIGHT) / 2.0,
+            (end - start).max(1.0),
+            colour(band),
+            escape(&bar.item.name),
+            band,
+            bar.item.urgency,
+            end + 6.0,
+            y + ROW_HEIGHT / 2.0 + 4.0,
+            escape(label)
+        );
+    }
+
+    // Gridlines and labels at the first day of each month
+    fn months(&self, height: f64) -> String {
+        let mut svg = String::new();
+        let last = self.first + Duration::days(self.days);
+        let mut month = NaiveDate::from_ymd_opt(self.first.year(), self.first.month(), 1);
+        while let Some(date) = month.filter(|date| *date <= last) {
+            if date >= self.first {
+                let x = self.x(date);
+                svg.push_str(&format!(
+                    "<line class=\"grid\" x1=\"{x:.1}\" y1=\"{0:.1}\" x2=\"{x:.1}\" \
+                     y2=\"{height}\"/>\n<text class=\"month\" x=\"{1:.1}\" y=\"{0:.1}\">\
+                     {2}</text>\n",
+                    HEADER_HEIGHT + 12.0,
+                    x + 4.0,
+                    date.format("%b %Y")
+                ));
+            }
+            month = date.checked_add_months(Months::new(1));
+        }
+        return svg;
+    }
+}
+
+fn legend(thresholds: &Thresholds) -> String {
+    let mut svg = String::from("<text x=\"0\" y=\"20\">Urgency</text>\n");
+    for (i, band) in BANDS.iter().enumerate() {
+        let x = 64.0 + i as f64 * 150.0;
+        svg.push_str(&format!(
+            "<rect x=\"{x}\" y=\"9\" width=\"14\" height=\"14\" rx=\"3\" fill=\"{0}\"/>\n\
+             <text x=\"{1}\" y=\"20\">{2}</text>\n",
+            colour(*band),
+            x + 20.0,
+            band_label(*band, thresholds)
+        ));
+    }
+    return svg;
+}
+
+fn band_label(band: UrgencyBand, thresholds: &Thresholds) -> String {
+    match band {
+        UrgencyBand::High => format!("high, from {0}", thresholds.second),
+        UrgencyBand::Medium => format!("medium, from {0}", thresholds.first),
+        UrgencyBand::Low => format!("low, below {0}", thresholds.first),
+    }
+}
+
+/// Names of the contributors of the item, or unstaffed when it has none.
+fn staffing(item: &ItemReport) -> String {
+    if item.contributors.is_empty() {
+        return String::from("unstaffed");
+    }
+    return item
+        .contributors
+        .iter()
+        .map(|contributor| contributor.name.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+}
+
+fn section(title: &str, top: f64) -> String {
+    return format!(
+        "<text class=\"section\" x=\"0\" y=\"{0:.1}\">{title}</text>\n",
+        top - 10.0
+    );
+}
+
+fn row_label(name: &str, y: f64) -> String {
+    let short = if name.chars().count() > MAX_LABEL_CHARS {
+        format!(
+            "{0}…",
+            name.chars().take(MAX_LABEL_CHARS - 1).collect::<String>()
+        )
+    } else {
+        String::from(name)
+    };
+    return format!(
+        "<text x=\"0\" y=\"{0:.1}\"><title>{1}</title>{2}</text>\n",
+        y + ROW_HEIGHT / 2.0 + 4.0,
+        escape(name),
+        escape(&short)
+    );
+}
+
+fn task_label(item: &ItemReport) -> String {
+    return format!("{0} ({1})", item.name, staffing(item));
+}
+
+// Colons and semicolons separate the parts of a Mermaid task, and # starts a comment
+fn mermaid_text(text: &str) -> String {
+    return text.replace([':', ';', '#', '\n'], " ");
+}
+
+// Square brackets delimit PlantUML task names
+fn plantuml_text(text: &str) -> String {
+    return text.replace('[', "(").replace(']', ")").replace('\n', " ");
+}
+
+fn colour(band: UrgencyBand) -> &'static str {
+    match band {
+        UrgencyBand::High => "#d7301f",
+        UrgencyBand::Medium => "#fc8d59",
+        UrgencyBand::Low => "#4292c6",
+    }
+}
+
+fn escape(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::config::Config;
+    use crate::contributor::Contributor;
+    use crate::report::PlanReport;
+    use crate::roadmap::RoadmapItem;
+
+    #[test]
+    fn draws_gantt_charts_and_diagrams() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let ada = Contributor::new(String::from("Ada"), 5);
+        let bob = Contributor::new(String::from("Bob"), 1);
+        let mut app = RoadmapItem::new(
+            String::from("App <v2>"),
+            1,
+            1,
+            today,
+            today + Duration::days(30),
+            vec![ada.allocate(0.5)],
+        );
+        app.urgency = Some(0.7);
+        app.set_schedule(today, today + Duration::days(9));
+        let mut web = RoadmapItem::new(
+            String::from("Web"),
+            1,
+            1,
+            today,
+            today + Duration::days(60),
+            vec![],
+        );
+        web.urgency = Some(0.1);
+        let report = PlanReport::new(&[app, web], &[ada, bob], today, &Config::default());
+
+        let mut output: Vec<u8> = Vec::new();
+        report.write_html(&mut output).unwrap();
+        let html = String::from_utf8(output).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Plan as of 2022-10-17</title>"));
+        // One bar per item and one in Ada's swimlane
+        assert_eq!(html.matches("<rect class=\"bar").count(), 3);
+        assert!(html.contains("fill=\"#d7301f\""));
+        assert!(html.contains("<rect class=\"bar unscheduled\""));
+        assert!(html.contains(">App &lt;v2&gt; (50%)</text>"));
+        assert!(html.contains(">Ada</text>"));
+        assert!(html.contains(">unstaffed</text>"));
+        assert!(html.contains(">idle</text>"));
+        assert!(html.contains("<line class=\"today\" x1=\"200.0\""));
+        assert!(html.contains(">Nov 2022</text>"));
+
+        let mut output: Vec<u8> = Vec::new();
+        report.write_mermaid(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "gantt\n    title Plan as of 2022-10-17\n    dateFormat YYYY-MM-DD\n    axisFormat %b %d\n\
+             \x20   section Urgency high, from 0.6\n    App <v2> (Ada) :2022-10-17, 2022-10-27\n\
+             \x20   section Urgency low, below 0.3\n    Web (unstaffed) :2022-10-17, 2022-12-17\n"
+        );
+        let mut output: Vec<u8> = Vec::new();
+        report.write_plantuml(&mut output).unwrap();
+        let plantuml = String::from_utf8(output).unwrap();
+        assert!(plantuml.contains("today is 0 days after start"));
+        assert!(plantuml.contains(
+            "-- Urgency high, from 0.6 --\n[App <v2> (Ada)] starts 2022-10-17 and ends 2022-10-26\n"
+        ));
+        assert!(plantuml.ends_with("[Web (unstaffed)] is colored in #4292c6\n@endgantt\n"));
+    }
+}
@@ -0,0 +1,707 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidateArgs};
+
+use crate::calendar::{parse_ics, Absence, Calendar};
+use crate::clock::Clock;
+use crate::contributor::Contributor;
+use crate::dependency::topological_order;
+use crate::error::KapacitetError;
+use crate::report::{parse_allocations, ContributorReport, ItemReport, SCHEMA_VERSION};
+use crate::roadmap::RoadmapItem;
+use crate::skill::parse_skills;
+
+/// Formats that contributors and roadmap items can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Csv,
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl InputFormat {
+    /// Detects the format from the extension of the file, falling back to CSV.
+    pub fn detect(file_path: &str) -> InputFormat {
+        let extension = Path::new(file_path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("json") => InputFormat::Json,
+            Some("yaml") | Some("yml") => InputFormat::Yaml,
+            Some("toml") => InputFormat::Toml,
+            _ => InputFormat::Csv,
+        }
+    }
+}
+
+/// Reads contributors from a file in the format given by its extension.
+pub fn read_contributors_file(file_path: &str) -> Result<Vec<Contributor>, KapacitetError> {
+    return read_contributors_file_as(file_path, InputFormat::detect(file_path));
+}
+
+pub fn read_contributors_file_as(
+    file_path: &str,
+    format: InputFormat,
+) -> Result<Vec<Contributor>, KapacitetError> {
+    if format != InputFormat::Csv {
+        let contents = read_file(file_path)?;
+        return contributors_from_document(file_path, contents.as_str(), format);
+    }
+    match csv::Reader::from_path(file_path) {
+        Ok(rdr) => {
+            return contributors_from_csv(rdr);
+        }
+        Err(err) => {
+            return Err(KapacitetError::UnreadableFile(String::from(file_path), err));
+        }
+    }
+}
+
+pub fn read_contributors<R: Read>(reader: R) -> Result<Vec<Contributor>, KapacitetError> {
+    return read_contributors_as(reader, InputFormat::Csv);
+}
+
+pub fn read_contributors_as<R: Read>(
+    reader: R,
+    format: InputFormat,
+) -> Result<Vec<Contributor>, KapacitetError> {
+    if format != InputFormat::Csv {
+        let source = format.to_string();
+        let contents = read_all(source.as_str(), reader)?;
+        return contributors_from_document(source.as_str(), contents.as_str(), format);
+    }
+    return contributors_from_csv(csv::Reader::from_reader(reader));
+}
+
+/// Reads roadmap items from a file in the format given by its extension.
+pub fn read_roadmap_file(
+    file_path: &str,
+    clock: &dyn Clock,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    return read_roadmap_file_as(file_path, InputFormat::detect(file_path), clock);
+}
+
+pub fn read_roadmap_file_as(
+    file_path: &str,
+    format: InputFormat,
+    clock: &dyn Clock,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    if format != InputFormat::Csv {
+        let contents = read_file(file_path)?;
+        return roadmap_items_from_document(file_path, contents.as_str(), format, clock.today());
+    }
+    match csv::Reader::from_path(file_path) {
+        Ok(rdr) => {
+            return roadmap_items_from_csv(rdr, clock.today());
+        }
+        Err(err) => {
+            return Err(KapacitetError::UnreadableFile(String::from(file_path), err));
+        }
+    }
+}
+
+pub fn read_roadmap<R: Read>(
+    reader: R,
+    clock: &dyn Clock,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    return read_roadmap_as(reader, InputFormat::Csv, clock);
+}
+
+pub fn read_roadmap_as<R: Read>(
+    reader: R,
+    format: InputFormat,
+    clock: &dyn Clock,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    if format != InputFormat::Csv {
+        let source = format.to_string();
+        let contents = read_all(source.as_str(), reader)?;
+        return roadmap_items_from_document(
+            source.as_str(),
+            contents.as_str(),
+            format,
+            clock.today(),
+        );
+    }
+    return roadmap_items_from_csv(csv::Reader::from_reader(reader), clock.today());
+}
+
+/// Reads team holidays and time off from an iCalendar file if it ends in `.ics` and from
+/// a CSV file otherwise.
+pub fn read_calendar_file(file_path: &str) -> Result<Calendar, KapacitetError> {
+    let is_ics = Path::new(file_path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"));
+    if is_ics {
+        let contents = fs::read_to_string(file_path)
+            .map_err(|err| KapacitetError::UnreadableCalendar(String::from(file_path), err))?;
+        return Ok(Calendar::new(parse_ics(file_path, contents.as_str())?));
+    }
+    match csv::Reader::from_path(file_path) {
+        Ok(rdr) => {
+            return calendar_from_csv(rdr);
+        }
+        Err(err) => {
+            return Err(KapacitetError::UnreadableFile(String::from(file_path), err));
+        }
+    }
+}
+
+/// Reads team holidays and time off from CSV.
+pub fn read_calendar<R: Read>(reader: R) -> Result<Calendar, KapacitetError> {
+    return calendar_from_csv(csv::Reader::from_reader(reader));
+}
+
+/// Reads the items of a plan from the JSON output of `kapacitet plan` if the file ends in
+/// `.json`, and otherwise from its CSV output in the wide or long layout. The columns
+/// breaking down the urgency score are ignored.
+pub fn read_plan_file(file_path: &str, delimiter: u8) -> Result<Vec<ItemReport>, KapacitetError> {
+    if InputFormat::detect(file_path) == InputFormat::Json {
+        let contents = read_file(file_path)?;
+        return read_plan_json(contents.as_bytes());
+    }
+    match plan_reader(delimiter).from_path(file_path) {
+        Ok(rdr) => {
+            return plan_from_csv(rdr);
+        }
+        Err(err) => {
+            return Err(KapacitetError::UnreadableFile(String::from(file_path), err));
+        }
+    }
+}
+
+pub fn read_plan<R: Read>(reader: R, delimiter: u8) -> Result<Vec<ItemReport>, KapacitetError> {
+    return plan_from_csv(plan_reader(delimiter).from_reader(reader));
+}
+
+/// Reads the items of a plan from the JSON output of `kapacitet plan`.
+pub fn read_plan_json<R: Read>(reader: R) -> Result<Vec<ItemReport>, KapacitetError> {
+    let malformed = KapacitetError::MalformedPlan;
+    let mut report: serde_json::Value =
+        serde_json::from_reader(reader).map_err(|err| malformed(err.to_string()))?;
+    let schema_version = report
+        .get("schema_version")
+        .and_then(serde_json::Value::as_u64);
+    if schema_version.is_none_or(|version| version > SCHEMA_VERSION as u64) {
+        return Err(malformed(format!(
+            "Only schema versions up to {SCHEMA_VERSION} are supported"
+        )));
+    }
+    match report.get_mut("items").map(serde_json::Value::take) {
+        Some(items) => {
+            return serde_json::from_value(items).map_err(|err| malformed(err.to_string()));
+        }
+        None => {
+            return Err(malformed(String::from("The items are missing")));
+        }
+    }
+}
+
+fn plan_reader(delimiter: u8) -> csv::ReaderBuilder {
+    let mut builder = csv::ReaderBuilder::new();
+    builder.delimiter(delimiter);
+    return builder;
+}
+
+fn plan_from_csv<R: Read>(mut rdr: csv::Reader<R>) -> Result<Vec<ItemReport>, KapacitetError> {
+    let malformed = |err: csv::Error| KapacitetError::MalformedPlan(err.to_string());
+    let header = rdr.headers().map_err(malformed)?.clone();
+    let column = |name: &str| -> Result<usize, KapacitetError> {
+        return header
+            .iter()
+            .position(|field| field == name)
+            .ok_or_else(|| KapacitetError::MalformedPlan(format!("The column {name} is missing")));
+    };
+    let name = column("name")?;
+    let start_date = column("start date")?;
+    let target_date = column("target date")?;
+    let urgency = column("urgency (0-1)")?;
+    let scheduled_start = column("scheduled start")?;
+    let scheduled_end = column("scheduled end")?;
+    let skill_gaps = column("skill gaps")?;
+    // The wide layout lists all contributors in one column, the long one has a row for each
+    let contributors = column("contributors").ok();
+    let long_columns = match contributors {
+        Some(_) => None,
+        None => Some((
+            column("contributor")?,
+            column("seniority")?,
+            column("allocation")?,
+        )),
+    };
+
+    let mut items: Vec<ItemReport> = Vec::new();
+    for record_result in rdr.records() {
+        let record = record_result.map_err(malformed)?;
+        let line = record
+            .position()
+            .map(|position| position.line())
+            .unwrap_or(0);
+        let field = |i: usize| record.get(i).unwrap_or("").trim();
+        let invalid =
+            |reason: String| KapacitetError::MalformedPlan(format!("Line {line}: {reason}"));
+        let date = |i: usize| -> Result<Option<NaiveDate>, KapacitetError> {
+            if field(i).is_empty() {
+                return Ok(None);
+            }
+            return field(i)
+                .parse::<NaiveDate>()
+                .map(Some)
+                .map_err(|err| invalid(format!("Could not parse the date {0}. {err}", field(i))));
+        };
+        let number = |i: usize| -> Result<f64, KapacitetError> {
+            return field(i).parse::<f64>().map_err(|err| {
+                invalid(format!("Could not parse the number {0}. {err}", field(i)))
+            });
+        };
+
+        let assigned = match (contributors, long_columns) {
+            (Some(i), _) => parse_allocations(field(i)).map_err(invalid)?,
+            (None, Some((contributor, _, _))) if field(contributor).is_empty() => Vec::new(),
+            (None, Some((contributor, seniority, allocation))) => vec![ContributorReport {
+                name: String::from(field(contributor)),
+                seniority: number(seniority)? as usize,
+                allocation: number(allocation)?,
+            }],
+            (None, None) => Vec::new(),
+        };
+        // Rows of the long layout for the same item follow each other
+        if let Some(item) = items.last_mut() {
+            if long_columns.is_some() && item.name == field(name) {
+                item.contributors.extend(assigned);
+                continue;
+            }
+        }
+        items.push(ItemReport {
+            name: String::from(field(name)),
+            start_date: date(start_date)?
+                .ok_or_else(|| invalid(String::from("The start date is missing")))?,
+            target_date: date(target_date)?
+                .ok_or_else(|| invalid(String::from("The target date is missing")))?,
+            urgency: number(urgency)?,
+            score_breakdown: None,
+            scheduled_start: date(scheduled_start)?,
+            scheduled_end: date(scheduled_end)?,
+            contributors: assigned,
+            required_skills: Vec::new(),
+            skill_gaps: parse_skills(field(skill_gaps)).map_err(invalid)?,
+            depends_on: Vec::new(),
+        });
+    }
+    return Ok(items);
+}
+
+fn calendar_from_csv<R: Read>(mut rdr: csv::Reader<R>) -> Result<Calendar, KapacitetError> {
+    let mut absences: Vec<Absence> = Vec::new();
+    for absence_result in rdr.deserialize::<Absence>() {
+        let absence = absence_result.map_err(KapacitetError::MalformedAbsence)?;
+        if let Err(errors) = absence.validate() {
+            return Err(KapacitetError::InvalidAbsence(absence.description, errors));
+        }
+        absences.push(absence);
+    }
+    return Ok(Calendar::new(absences));
+}
+
+fn contributors_from_csv<R: Read>(
+    mut rdr: csv::Reader<R>,
+) -> Result<Vec<Contributor>, KapacitetError> {
+    let mut contributors: Vec<Contributor> = Vec::new();
+    for contributor_result in rdr.deserialize::<Contributor>() {
+        let contributor = contributor_result.map_err(KapacitetError::MalformedContributor)?;
+        contributors.push(validate_contributor(contributor)?);
+    }
+    return Ok(contributors);
+}
+
+pub(crate) fn validate_contributors(
+    contributors: Vec<Contributor>,
+) -> Result<Vec<Contributor>, KapacitetError> {
+    return contributors.into_iter().map(validate_contributor).collect();
+}
+
+fn contributors_from_document(
+    source: &str,
+    contents: &str,
+    format: InputFormat,
+) -> Result<Vec<Contributor>, KapacitetError> {
+    let contributors = records_from_document(source, contents, format, "contributors")?;
+    return validate_contributors(contributors);
+}
+
+fn validate_contributor(contributor: Contributor) -> Result<Contributor, KapacitetError> {
+    match contributor.validate() {
+        Ok(()) => {
+            return Ok(contributor);
+        }
+        Err(errors) => {
+            return Err(KapacitetError::InvalidContributor(contributor.name, errors));
+        }
+    }
+}
+
+fn roadmap_items_from_csv<R: Read>(
+    mut rdr: csv::Reader<R>,
+    today: NaiveDate,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    let mut roadmap_items: Vec<RoadmapItem> = Vec::new();
+    for roadmap_item_result in rdr.deserialize::<RoadmapItem>() {
+        let roadmap_item = roadmap_item_result.map_err(KapacitetError::MalformedRoadmapItem)?;
+        roadmap_items.push(validate_roadmap_item(roadmap_item, today)?);
+    }
+    // Dependencies can only be checked once every item has been read
+    topological_order(&roadmap_items)?;
+    return Ok(roadmap_items);
+}
+
+fn roadmap_items_from_document(
+    source: &str,
+    contents: &str,
+    format: InputFormat,
+    today: NaiveDate,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    let roadmap_items = records_from_document(source, contents, format, "roadmap")?;
+    return validate_roadmap_items(roadmap_items, today);
+}
+
+pub(crate) fn validate_roadmap_items(
+    roadmap_items: Vec<RoadmapItem>,
+    today: NaiveDate,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    let roadmap_items = roadmap_items
+        .into_iter()
+        .map(|roadmap_item| validate_roadmap_item(roadmap_item, today))
+        .collect::<Result<Vec<RoadmapItem>, KapacitetError>>()?;
+    topological_order(&roadmap_items)?;
+    return Ok(roadmap_items);
+}
+
+fn validate_roadmap_item(
+    roadmap_item: RoadmapItem,
+    today: NaiveDate,
+) -> Result<RoadmapItem, KapacitetError> {
+    match roadmap_item.validate_args(&today) {
+        Ok(()) => {
+            return Ok(roadmap_item);
+        }
+        Err(errors) => {
+            return Err(KapacitetError::InvalidRoadmapItem(
+                roadmap_item.name,
+                errors,
+            ));
+        }
+    }
+}
+
+// JSON and YAML documents hold either a list of records or a map with the list under `key`,
+// which is the only option in TOML, e.g. `[[contributors]]`
+fn records_from_document<T: DeserializeOwned>(
+    source: &str,
+    contents: &str,
+    format: InputFormat,
+    key: &str,
+) -> Result<Vec<T>, KapacitetError> {
+    let values = values_from_document(source, contents, format, key)?;
+    return records_from_values(source, values, key);
+}
+
+/// Deserializes records from TOML, where dates have their own type.
+pub(crate) fn records_from_toml<T: DeserializeOwned>(
+    source: &str,
+    document: toml::Value,
+    key: &str,
+) -> Result<Vec<T>, KapacitetError> {
+    let values = values_from_toml(source, document, key)?;
+    return records_from_values(source, values, key);
+}
+
+/// Splits a JSON, YAML or TOML document into the values of its records.
+pub(crate) fn values_from_document(
+    source: &str,
+    contents: &str,
+    format: InputFormat,
+    key: &str,
+) -> Result<Vec<serde_json::Value>, KapacitetError> {
+    let malformed = |reason: String| KapacitetError::MalformedInput(String::from(source), reason);
+    let document: serde_json::Value = match format {
+        InputFormat::Json => {
+            serde_json::from_str(contents).map_err(|err| malformed(err.to_string()))?
+        }
+        InputFormat::Yaml => {
+            serde_yaml::from_str(contents).map_err(|err| malformed(err.to_string()))?
+        }
+        InputFormat::Toml => {
+            let document: toml::Value =
+                toml::from_str(contents).map_err(|err| malformed(err.to_string()))?;
+            return values_from_toml(source, document, key);
+        }
+        InputFormat::Csv => unreachable!("CSV is read record by record"),
+    };
+    return values_from_value(source, document, key);
+}
+
+pub(crate) fn values_from_toml(
+    source: &str,
+    mut document: toml::Value,
+    key: &str,
+) -> Result<Vec<serde_json::Value>, KapacitetError> {
+    dates_to_strings(&mut document);
+    match serde_json::to_value(document) {
+        Ok(document) => {
+            return values_from_value(source, document, key);
+        }
+        Err(err) => {
+            return Err(KapacitetError::MalformedInput(
+                String::from(source),
+                err.to_string(),
+            ));
+        }
+    }
+}
+
+fn values_from_value(
+    source: &str,
+    document: serde_json::Value,
+    key: &str,
+) -> Result<Vec<serde_json::Value>, KapacitetError> {
+    let values = match document {
+        serde_json::Value::Array(values) => Some(values),
+        serde_json::Value::Object(mut map) => match map.remove(key) {
+            Some(serde_json::Value::Array(values)) => Some(values),
+            _ => None,
+        },
+        _ => None,
+    };
+    return values.ok_or_else(|| {
+        KapacitetError::MalformedInput(String::from(source), format!("Expected a list of {key}"))
+    });
+}
+
+fn records_from_values<T: DeserializeOwned>(
+    source: &str,
+    values: Vec<serde_json::Value>,
+    key: &str,
+) -> Result<Vec<T>, KapacitetError> {
+    return values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            T::deserialize(value).map_err(|err| {
+                KapacitetError::MalformedInput(
+                    String::from(source),
+                    format!("{key} #{0}: {err}", i + 1),
+                )
+            })
+        })
+        .collect();
+}
+
+// TOML has its own date type, while dates are read from strings everywhere else
+fn dates_to_strings(value: &mut toml::Value) {
+    match value {
+        toml::Value::Datetime(datetime) => {
+            *value = toml::Value::String(datetime.to_string());
+        }
+        toml::Value::Array(values) => values.iter_mut().for_each(dates_to_strings),
+        toml::Value::Table(table) => table
+            .iter_mut()
+            .for_each(|(_, value)| dates_to_strings(value)),
+        _ => {}
+    }
+}
+
+fn read_file(file_path: &str) -> Result<String, KapacitetError> {
+    return fs::read_to_string(file_path)
+        .map_err(|err| KapacitetError::UnreadableFile(String::from(file_path), err.into()));
+}
+
+fn read_all<R: Read>(source: &str, mut reader: R) -> Result<String, KapacitetError> {
+    let mut contents = String::new();
+    match reader.read_to_string(&mut contents) {
+        Ok(_) => {
+            return Ok(contents);
+        }
+        Err(err) => {
+            return Err(KapacitetError::UnreadableFile(
+                String::from(source),
+                err.into(),
+            ));
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<InputFormat, String> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(InputFormat::Csv),
+            "json" => Ok(InputFormat::Json),
+            "yaml" | "yml" => Ok(InputFormat::Yaml),
+            "toml" => Ok(InputFormat::Toml),
+            _ => Err(format!(
+                "Unknown input format {s}. Use one of csv, json, yaml or toml"
+            )),
+        }
+    }
+}
+
+impl Display for InputFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            InputFormat::Csv => write!(f, "CSV"),
+            InputFormat::Json => write!(f, "JSON"),
+            InputFormat::Yaml => write!(f, "YAML"),
+            InputFormat::Toml => write!(f, "TOML"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::clock::FixedClock;
+    use crate::error::KapacitetError;
+    use crate::input::{
+        read_calendar, read_contributors, read_contributors_as, read_roadmap, read_roadmap_as,
+        read_roadmap_file, InputFormat,
+    };
+    use crate::skill::Skill;
+
+    #[test]
+    fn reads_valid_files() {
+        let contributors = read_contributors("name,seniority\nIC1,5\nIC2,1\n".as_bytes()).unwrap();
+        assert_eq!(contributors.len(), 2);
+
+        let roadmap_items = read_roadmap(
+            "name,estimated_complexity,estimated_value,start_date,target_date\nMVP,4,5,2022-06-01,2022-11-01\n"
+                .as_bytes(),
+            &FixedClock(NaiveDate::from_ymd_opt(2022, 10, 15).unwrap()),
+        )
+        .unwrap();
+        assert_eq!(roadmap_items.len(), 1);
+        assert_eq!(roadmap_items[0].name, "MVP");
+    }
+
+    #[test]
+    fn reads_skills() {
+        let contributors =
+            read_contributors("name,seniority,skills\nIC1,5,rust:4;ios\nIC2,1,\n".as_bytes())
+                .unwrap();
+        assert_eq!(
+            contributors[0].skills,
+            vec![Skill::new("rust", 4), Skill::new("ios", 1)]
+        );
+        assert!(contributors[1].skills.is_empty());
+        assert_eq!(contributors[0].capacity, 1.0);
+    }
+
+    #[test]
+    fn reads_holidays_and_time_off() {
+        let calendar = read_calendar(
+            "contributor,start_date,end_date,description\n,2022-12-26,,Boxing Day\nIC1,2022-10-31,2022-11-04,Vacation\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(calendar.absences.len(), 2);
+        assert_eq!(calendar.absences[0].contributor, None);
+        assert_eq!(
+            calendar.absences[0].get_end_date(),
+            NaiveDate::from_ymd_opt(2022, 12, 26).unwrap()
+        );
+        assert_eq!(calendar.absences[1].contributor.as_deref(), Some("IC1"));
+        assert!(matches!(
+            read_calendar(
+                "contributor,start_date,end_date\nIC1,2022-11-04,2022-10-31\n".as_bytes()
+            ),
+            Err(KapacitetError::InvalidAbsence(_, _))
+        ));
+        assert!(matches!(
+            read_contributors("name,seniority,skills\nIC1,5,rust:9\n".as_bytes()),
+            Err(KapacitetError::InvalidContributor(name, _)) if name == "IC1"
+        ));
+    }
+
+    #[test]
+    fn returns_errors_instead_of_exiting() {
+        assert!(matches!(
+            read_contributors("name,seniority\nIC1,7\n".as_bytes()),
+            Err(KapacitetError::InvalidContributor(name, _)) if name == "IC1"
+        ));
+        assert!(matches!(
+            read_contributors("name,seniority\nIC1,senior\n".as_bytes()),
+            Err(KapacitetError::MalformedContributor(_))
+        ));
+        assert!(matches!(
+            read_roadmap(
+                "name,estimated_complexity,estimated_value,start_date,target_date\nMVP,4,5,2022-06-01,2022-11-01\n"
+                    .as_bytes(),
+                &FixedClock(NaiveDate::from_ymd_opt(2022, 12, 1).unwrap()),
+            ),
+            Err(KapacitetError::InvalidRoadmapItem(name, _)) if name == "MVP"
+        ));
+        assert!(matches!(
+            read_roadmap_file(
+                "does-not-exist.csv",
+                &FixedClock(NaiveDate::from_ymd_opt(2022, 10, 15).unwrap()),
+            ),
+            Err(KapacitetError::UnreadableFile(_, _))
+        ));
+    }
+
+    #[test]
+    fn reads_json_yaml_and_toml() {
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2022, 10, 15).unwrap());
+        let documents = [
+            (
+                InputFormat::Json,
+                r#"[{"name": "IC1", "seniority": 5, "skills": {"rust": 4}, "capacity": 0.5}]"#,
+                r#"{"roadmap": [{"name": "MVP", "estimated_complexity": 4, "estimated_value": 5, "start_date": "2022-06-01", "target_date": "2022-11-01", "required_skills": ["rust:3"]}]}"#,
+            ),
+            (
+                InputFormat::Yaml,
+                "- name: IC1\n  seniority: 5\n  skills: rust:4\n  capacity: 0.5\n",
+                "- name: MVP\n  estimated_complexity: 4\n  estimated_value: 5\n  start_date: 2022-06-01\n  target_date: 2022-11-01\n  required_skills: [rust:3]\n",
+            ),
+            (
+                InputFormat::Toml,
+                "[[contributors]]\nname = \"IC1\"\nseniority = 5\nskills = [\"rust:4\"]\ncapacity = 0.5\n",
+                "[[roadmap]]\nname = \"MVP\"\nestimated_complexity = 4\nestimated_value = 5\nstart_date = 2022-06-01\ntarget_date = 2022-11-01\nrequired_skills = \"rust:3\"\n",
+            ),
+        ];
+        for (format, contributors, roadmap) in documents {
+            let contributors = read_contributors_as(contributors.as_bytes(), format).unwrap();
+            assert_eq!(contributors[0].skills, vec![Skill::new("rust", 4)]);
+            assert_eq!(contributors[0].capacity, 0.5);
+            let roadmap_items = read_roadmap_as(roadmap.as_bytes(), format, &clock).unwrap();
+            assert_eq!(roadmap_items[0].name, "MVP");
+            assert_eq!(
+                roadmap_items[0].target_date,
+                NaiveDate::from_ymd_opt(2022, 11, 1).unwrap()
+            );
+            assert_eq!(
+                roadmap_items[0].required_skills,
+                vec![Skill::new("rust", 3)]
+            );
+        }
+
+        assert!(matches!(
+            read_contributors_as(r#"[{"name": "IC1", "seniority": 7}]"#.as_bytes(), InputFormat::Json),
+            Err(KapacitetError::InvalidContributor(name, _)) if name == "IC1"
+        ));
+        assert!(matches!(
+            read_contributors_as("name = \"IC1\"".as_bytes(), InputFormat::Toml),
+            Err(KapacitetError::MalformedInput(_, _))
+        ));
+        assert_eq!(InputFormat::detect("team.YML"), InputFormat::Yaml);
+    }
+}
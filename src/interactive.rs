@@ -0,0 +1,340 @@
+use std::io;
+use std::io::Write;
+
+use chrono::NaiveDate;
+use kapacitet::clock::Clock;
+use kapacitet::contributor::Contributor;
+use kapacitet::dependency::parse_names;
+use kapacitet::input;
+use kapacitet::roadmap::{Moscow, RoadmapItem};
+use kapacitet::scoring::Model;
+use kapacitet::skill::{parse_skills, Skill};
+
+use crate::unwrap_or_exit;
+
+pub fn create_contributors() -> Vec<Contributor> {
+    let mut contributors: Vec<Contributor> = Vec::new();
+    loop {
+        print!("Do you have a contributors file? (y/n): ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+
+        match input.trim_end().to_lowercase().as_str() {
+            "y" => {
+                contributors.append(&mut unwrap_or_exit(input::read_contributors_file(
+                    read_file_path().as_str(),
+                )));
+                return contributors;
+            }
+            "n" => {
+                contributors.append(&mut create_contributors_from_stdin());
+                return contributors;
+            }
+            _ => {
+                continue;
+            }
+        }
+    }
+}
+
+fn create_contributors_from_stdin() -> Vec<Contributor> {
+    let mut contributors: Vec<Contributor> = Vec::new();
+    println!("Let's add our first contributor!");
+    contributors.push(create_contributor_from_stdin());
+    loop {
+        print!("Add another contributor? (y/n): ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+
+        match input.trim_end().to_lowercase().as_str() {
+            "y" => {
+                contributors.push(create_contributor_from_stdin());
+            }
+            "n" => {
+                println!("All contributors added!");
+                return contributors;
+            }
+            _ => {
+                continue;
+            }
+        }
+    }
+}
+
+fn create_contributor_from_stdin() -> Contributor {
+    let name = parse_string("Contributor name");
+    let seniority = parse_number("Contributor seniority (1-5)", 1, 5);
+    let skills = parse_skill_list("Contributor skills");
+    let capacity = parse_decimal("Contributor capacity in FTE (0.1-1)", 0.1, 1.0);
+
+    let mut contributor = Contributor::new(name, seniority);
+    contributor.skills = skills;
+    contributor.capacity = capacity;
+    return contributor;
+}
+
+pub fn create_roadmap_items(clock: &dyn Clock, model: Model) -> Vec<RoadmapItem> {
+    println!("Let's add all roadmap items!");
+    let mut roadmap_items: Vec<RoadmapItem> = Vec::new();
+    loop {
+        print!("Do you have a roadmap file? (y/n): ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+
+        match input.trim_end().to_lowercase().as_str() {
+            "y" => {
+                roadmap_items.append(&mut unwrap_or_exit(input::read_roadmap_file(
+                    read_file_path().as_str(),
+                    clock,
+                )));
+                return roadmap_items;
+            }
+            "n" => {
+                roadmap_items.append(&mut create_roadmap_items_from_stdin(clock.today(), model));
+                return roadmap_items;
+            }
+            _ => {
+                continue;
+            }
+        }
+    }
+}
+
+fn create_roadmap_items_from_stdin(today: NaiveDate, model: Model) -> Vec<RoadmapItem> {
+    let mut roadmap_items: Vec<RoadmapItem> = Vec::new();
+    println!("Let's create our first roadmap item!");
+    roadmap_items.push(create_roadmap_item_from_stdin(today, model));
+    loop {
+        print!("Add another roadmap item? (y/n): ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+
+        match input.trim_end().to_lowercase().as_str() {
+            "y" => {
+                roadmap_items.push(create_roadmap_item_from_stdin(today, model));
+            }
+            "n" => {
+                println!("All roadmap items added!");
+                return roadmap_items;
+            }
+            _ => {
+                continue;
+            }
+        }
+    }
+}
+
+fn create_roadmap_item_from_stdin(today: NaiveDate, model: Model) -> RoadmapItem {
+    let name = parse_string("Roadmap item name");
+    let estimated_complexity = parse_number("Estimated complexity (1-5)", 1, 5);
+    let estimated_value = parse_number("Estimated value (1-5)", 1, 5);
+    loop {
+        let start_date = parse_date("Start date");
+        let target_date = parse_date("Target date");
+        let date_diff = target_date.signed_duration_since(start_date).num_days();
+        if date_diff.is_negative() {
+            warn!("The target date cannot be before the start date.");
+            continue;
+        }
+
+        if target_date
+            .signed_duration_since(today)
+            .num_days()
+            .is_negative()
+        {
+            warn!("The target date cannot be before today.");
+            continue;
+        }
+
+        let mut roadmap_item = RoadmapItem::new(
+            name,
+            estimated_complexity,
+            estimated_value,
+            start_date,
+            target_date,
+            Vec::new(),
+        );
+        add_scoring_fields_from_stdin(&mut roadmap_item, model);
+        roadmap_item.required_skills = parse_skill_list("Required skills");
+        roadmap_item.depends_on = parse_names(&parse_string(
+            "Depends on (item names separated by ;, empty for none)",
+        ));
+        return roadmap_item;
+    }
+}
+
+fn add_scoring_fields_from_stdin(roadmap_item: &mut RoadmapItem, model: Model) {
+    match model {
+        Model::Weighted => {}
+        Model::Wsjf => {
+            roadmap_item.business_value = Some(parse_number("Business value (1-10)", 1, 10));
+            roadmap_item.time_criticality = Some(parse_number("Time criticality (1-10)", 1, 10));
+            roadmap_item.risk_reduction = Some(parse_number("Risk reduction (1-10)", 1, 10));
+            roadmap_item.job_size = Some(parse_number("Job size (1-10)", 1, 10));
+        }
+        Model::Rice => {
+            roadmap_item.reach = Some(parse_number("Reach (1-10)", 1, 10));
+            roadmap_item.impact = Some(parse_decimal("Impact (0.25-3)", 0.25, 3.0));
+            roadmap_item.confidence = Some(parse_number("Confidence in percent (0-100)", 0, 100));
+            roadmap_item.effort = Some(parse_number("Effort (1-10)", 1, 10));
+        }
+        Model::Moscow => {
+            roadmap_item.moscow = Some(parse_moscow("Must, should, could or won't have"));
+        }
+    }
+}
+
+fn parse_string(text: &'static str) -> String {
+    let mut input = String::new();
+    print!("{text}: ");
+    let _ = io::stdout().flush();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("Error reading from STDIN");
+    return String::from(input.trim_end());
+}
+
+fn parse_number(text: &'static str, min: usize, max: usize) -> usize {
+    loop {
+        print!("{text}: ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+        match input.trim_end().parse::<usize>() {
+            Ok(value) => {
+                if value < min || value > max {
+                    warn!("The value must be between {min} and {max}");
+                    continue;
+                }
+                return value;
+            }
+            Err(err) => {
+                warn!("Could not parse number: {err}");
+                continue;
+            }
+        };
+    }
+}
+
+fn parse_decimal(text: &'static str, min: f64, max: f64) -> f64 {
+    loop {
+        print!("{text}: ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+        match input.trim_end().parse::<f64>() {
+            Ok(value) => {
+                if value < min || value > max {
+                    warn!("The value must be between {min} and {max}");
+                    continue;
+                }
+                return value;
+            }
+            Err(err) => {
+                warn!("Could not parse number: {err}");
+                continue;
+            }
+        };
+    }
+}
+
+fn parse_moscow(text: &'static str) -> Moscow {
+    loop {
+        print!("{text} (must/should/could/wont): ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+        match input.trim_end().to_lowercase().as_str() {
+            "must" => {
+                return Moscow::Must;
+            }
+            "should" => {
+                return Moscow::Should;
+            }
+            "could" => {
+                return Moscow::Could;
+            }
+            "wont" | "won't" => {
+                return Moscow::Wont;
+            }
+            _ => {
+                continue;
+            }
+        }
+    }
+}
+
+fn parse_skill_list(text: &'static str) -> Vec<Skill> {
+    loop {
+        print!("{text} (e.g. rust:4;ios, empty for none): ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+        match parse_skills(input.trim_end()) {
+            Ok(skills) => {
+                if skills
+                    .iter()
+                    .any(|skill| skill.level < 1 || skill.level > 5)
+                {
+                    warn!("Skill levels must be between 1 and 5");
+                    continue;
+                }
+                return skills;
+            }
+            Err(err) => {
+                warn!("{err}");
+                continue;
+            }
+        };
+    }
+}
+
+fn parse_date(input_text: &'static str) -> NaiveDate {
+    loop {
+        print!("{input_text} (YYYY-mm-dd): ");
+        let mut input = String::new();
+        let _ = io::stdout().flush();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Error reading from STDIN");
+        match NaiveDate::parse_from_str(input.trim_end(), "%Y-%m-%d") {
+            Ok(date) => {
+                return date;
+            }
+            Err(err) => {
+                warn!("Could not parse date: {err}");
+                continue;
+            }
+        };
+    }
+}
+
+fn read_file_path() -> String {
+    print!("Please provide the absolute path to your file: ");
+    let mut input = String::new();
+    let _ = io::stdout().flush();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("Error reading from STDIN");
+    return String::from(input.trim_end());
+}
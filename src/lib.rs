@@ -0,0 +1,82 @@
+//! Kapacitet is a little library for roadmap and capacity planning. It
+//! calculates an urgency score for roadmap items, assigns contributors to
+//! them and schedules the items onto a timeline.
+#![allow(clippy::needless_return)]
+#[macro_use]
+extern crate log;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::calendar::Calendar;
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::contributor::Contributor;
+use crate::roadmap::RoadmapItem;
+
+pub mod calendar;
+pub mod clock;
+pub mod config;
+pub mod contributor;
+pub mod dependency;
+pub mod diff;
+pub mod error;
+pub mod gantt;
+pub mod input;
+pub mod project;
+pub mod report;
+pub mod roadmap;
+pub mod schedule;
+pub mod scoring;
+pub mod skill;
+pub mod solver;
+pub mod stability;
+pub mod table;
+pub mod validation;
+
+pub use crate::error::KapacitetError;
+
+/// Calculates the urgency of every roadmap item with the configured scoring model, lets
+/// the items others depend on inherit urgency from them and orders them from most to
+/// least urgent.
+pub fn score(
+    roadmap_items: &mut [RoadmapItem],
+    clock: &dyn Clock,
+    config: &Config,
+) -> Result<(), KapacitetError> {
+    let today = clock.today();
+    let model = config.model.build(config);
+    for roadmap_item in roadmap_items.iter_mut() {
+        roadmap_item.update_urgency(model.as_ref(), today)?;
+    }
+    dependency::propagate_urgency(roadmap_items, config.dependencies.inherited_urgency)?;
+    roadmap_items.sort();
+    return Ok(());
+}
+
+/// Scores the roadmap items that are neither done nor cancelled and schedules them with the
+/// given contributors on the working days of the calendar. Set the seed in the config to get
+/// the same assignment for the same inputs.
+pub fn plan(
+    mut roadmap_items: Vec<RoadmapItem>,
+    mut contributors: Vec<Contributor>,
+    calendar: &Calendar,
+    clock: &dyn Clock,
+    config: &Config,
+) -> Result<Vec<RoadmapItem>, KapacitetError> {
+    roadmap_items = roadmap::open_items(roadmap_items);
+    score(&mut roadmap_items, clock, config)?;
+    contributors.sort();
+
+    let seed = config.seed.unwrap_or_else(rand::random);
+    info!("Assigning contributors with seed {seed}");
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    return Ok(schedule::schedule_roadmap_items(
+        roadmap_items,
+        contributors,
+        calendar,
+        clock,
+        config,
+        &mut rng,
+    ));
+}
@@ -1,448 +1,708 @@
-extern crate core;
+#![allow(clippy::needless_return)]
 #[macro_use]
 extern crate log;
 extern crate simplelog;
 
-use std::io::Write;
-use std::{io, process};
-
-use chrono::NaiveDate;
-use rand::prelude::IteratorRandom;
+use std::env;
+use std::fs;
+use std::io;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process;
+
+use chrono::{Duration, NaiveDate};
+use clap::{Parser, ValueEnum};
+use kapacitet::calendar::Calendar;
+use kapacitet::clock::{Clock, FixedClock, SystemClock};
+use kapacitet::config::{Config, PROJECT_CONFIG_FILE};
+use kapacitet::contributor::Contributor;
+use kapacitet::diff::PlanDiff;
+use kapacitet::input;
+use kapacitet::input::InputFormat;
+use kapacitet::project::{Project, PROJECT_FILE};
+use kapacitet::report::{CsvOptions, ItemReport, PlanReport};
+use kapacitet::roadmap;
+use kapacitet::roadmap::RoadmapItem;
+use kapacitet::stability;
+use kapacitet::stability::Reassignment;
+use kapacitet::validation;
+use kapacitet::validation::{Problem, Record, Severity, Validation};
+use kapacitet::KapacitetError;
 use simplelog::*;
-use validator::Validate;
-
-use crate::contributor::Contributor;
-use crate::roadmap::RoadmapItem;
 
-mod contributor;
-mod roadmap;
+use crate::cli::{
+    Cli, Command, DiffArgs, DiffFormat, ExplainArgs, InitArgs, InteractiveArgs, OutputFormat,
+    PlanArgs, ScoreArgs,
+};
 
-static COMPLEXITY_FIRST_THRESHOLD: f64 = 0.3;
-static COMPLEXITY_SECOND_THRESHOLD: f64 = 0.6;
+mod cli;
+mod interactive;
 
 fn main() {
     CombinedLogger::init(vec![TermLogger::new(
         LevelFilter::Warn,
-        Config::default(),
-        TerminalMode::Mixed,
+        simplelog::Config::default(),
+        TerminalMode::Stderr,
         ColorChoice::Auto,
     )])
     .unwrap();
 
-    let mut contributors = create_contributors();
-    let mut roadmap_items = create_roadmap_items();
-    roadmap_items.sort();
-    contributors.sort();
-
-    let assigned_items = assign_contributors(roadmap_items, &mut contributors);
-
-    println!(
-        "{0},{1},{2},{3},{4}",
-        "name", "start date", "target date", "urgency (0-1)", "contributors"
-    );
-    assigned_items
-        .iter()
-        .for_each(|roadmap_item| println!("{roadmap_item}"));
-}
+    let cli = Cli::parse();
+    if let Command::Init(args) = cli.command {
+        init(args);
+        return;
+    }
 
-fn create_contributors() -> Vec<Contributor> {
-    let mut contributors: Vec<Contributor> = Vec::new();
-    loop {
-        print!("Do you have a contributors file? (y/n): ");
-        let mut input = String::new();
-        let _ = io::stdout().flush();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Error reading from STDIN");
-
-        match input.trim_end().to_lowercase().as_str() {
-            "y" => {
-                contributors.append(&mut create_contributors_from_file(read_file_path()));
-                return contributors;
-            }
-            "n" => {
-                contributors.append(&mut create_contributors_from_stdin());
-                return contributors;
-            }
-            _ => {
-                continue;
-            }
+    // The project file in the current directory is only used when input files are missing
+    let project = match &cli.command {
+        Command::Plan(args) => load_project(
+            cli.project.as_deref(),
+            args.contributors.is_none() || args.roadmap.is_none(),
+        ),
+        Command::Score(args) => load_project(cli.project.as_deref(), args.roadmap.is_none()),
+        Command::Explain(args) => load_project(cli.project.as_deref(), args.roadmap.is_none()),
+        Command::Validate(args) => load_project(
+            cli.project.as_deref(),
+            args.contributors.is_none() && args.roadmap.is_none() && args.calendar.is_empty(),
+        ),
+        _ => None,
+    };
+    let settings = project
+        .as_ref()
+        .map(|project| project.settings.clone())
+        .unwrap_or_default();
+    let mut config = unwrap_or_exit(Config::load_with(cli.config.as_deref(), settings));
+    match cli.command {
+        Command::Plan(args) => {
+            config.model = args.model.unwrap_or(config.model);
+            config.seed = Some(args.seed.or(config.seed).unwrap_or_else(rand::random));
+            config.strategy = args.strategy.unwrap_or(config.strategy);
+            config.skill_matching = args.skill_matching.unwrap_or(config.skill_matching);
+            check_explain(args.explain, args.output);
+            plan(args, &config, project.as_ref())
+        }
+        Command::Score(args) => {
+            config.model = args.model.unwrap_or(config.model);
+            check_explain(args.explain, args.output);
+            score(args, &config, project.as_ref())
+        }
+        Command::Explain(args) => {
+            config.model = args.model.unwrap_or(config.model);
+            explain(args, &config, project.as_ref())
+        }
+        Command::Validate(args) => validate(args, &config, project.as_ref()),
+        Command::Diff(args) => diff(args),
+        Command::Init(_) => unreachable!(),
+        Command::Interactive(args) => {
+            config.model = args.model.unwrap_or(config.model);
+            config.seed = Some(args.seed.or(config.seed).unwrap_or_else(rand::random));
+            config.strategy = args.strategy.unwrap_or(config.strategy);
+            config.skill_matching = args.skill_matching.unwrap_or(config.skill_matching);
+            check_explain(args.explain, args.output);
+            interactive(args, &config)
         }
     }
 }
 
-fn create_contributors_from_file(file_path: String) -> Vec<Contributor> {
-    loop {
-        let mut contributors: Vec<Contributor> = Vec::new();
-        match csv::Reader::from_path(file_path) {
-            Ok(mut rdr) => {
-                for contributor_result in rdr.deserialize::<Contributor>() {
-                    contributors.push(create_contributor_from_file(contributor_result));
-                }
-            }
-            Err(_) => {
-                error!("Unable to read contributors file. Make sure that it has the right format!");
-                process::exit(1);
-            }
-        };
-        return contributors;
+// Charts and diagrams have no room for the breakdown, which shouldn't go missing silently
+fn check_explain(explain: bool, output: OutputFormat) {
+    if explain && !output.shows_breakdown() {
+        let format = output
+            .to_possible_value()
+            .map(|value| String::from(value.get_name()))
+            .unwrap_or_default();
+        error!("--explain only works with csv, json, table and markdown output, not {format}");
+        process::exit(1);
     }
 }
 
-fn create_contributors_from_stdin() -> Vec<Contributor> {
-    let mut contributors: Vec<Contributor> = Vec::new();
-    println!("Let's add our first contributor!");
-    contributors.push(create_contributor_from_stdin());
-    loop {
-        print!("Add another contributor? (y/n): ");
-        let mut input = String::new();
-        let _ = io::stdout().flush();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Error reading from STDIN");
-
-        match input.trim_end().to_lowercase().as_str() {
-            "y" => {
-                contributors.push(create_contributor_from_stdin());
-            }
-            "n" => {
-                println!("All contributors added!");
-                return contributors;
-            }
-            _ => {
-                continue;
-            }
-        }
+fn plan(args: PlanArgs, config: &Config, project: Option<&Project>) {
+    let clock = clock(args.as_of.or(project.and_then(|project| project.as_of)));
+    let contributors = read_contributors(args.contributors.as_deref(), args.input_format, project);
+    let mut roadmap_items = read_roadmap(
+        args.roadmap.as_deref(),
+        args.input_format,
+        project,
+        clock.as_ref(),
+    );
+    let mut calendar = match project {
+        Some(project) => unwrap_or_exit(project.read_calendar()),
+        None => Calendar::default(),
+    };
+    calendar.extend(read_calendar_files(&args.calendar));
+    let previous_plan = args
+        .previous
+        .as_deref()
+        .map(|file_path| unwrap_or_exit(input::read_plan_file(file_path, args.delimiter)));
+    if let Some(previous_plan) = &previous_plan {
+        stability::carry_over(&mut roadmap_items, previous_plan);
     }
+    check_plan(
+        &contributors,
+        &roadmap_items,
+        &calendar,
+        clock.today(),
+        config,
+        &source(args.contributors.as_deref(), project),
+        &source(args.roadmap.as_deref(), project),
+    );
+    let mut report = plan_report(
+        roadmap_items,
+        contributors,
+        &calendar,
+        clock.as_ref(),
+        config,
+        previous_plan.as_deref(),
+    );
+    report.metadata.previous = args.previous.clone();
+    print_plan(
+        &report,
+        config,
+        args.output,
+        &CsvOptions {
+            delimiter: args.delimiter,
+            layout: args.layout,
+            explain: args.explain,
+        },
+    );
 }
 
-fn create_contributor_from_file(
-    contributor_result: Result<Contributor, csv::Error>,
-) -> Contributor {
-    match contributor_result {
-        Ok(contributor) => {
-            match contributor.validate() {
-                Ok(_) => {
-                    return contributor;
-                }
-                Err(_) => {
-                    error!("Invalid contributor {contributor}. Make sure that all contributors have valid values!");
-                    process::exit(1);
-                }
+fn score(args: ScoreArgs, config: &Config, project: Option<&Project>) {
+    let clock = clock(args.as_of.or(project.and_then(|project| project.as_of)));
+    let mut roadmap_items = roadmap::open_items(read_roadmap(
+        args.roadmap.as_deref(),
+        args.input_format,
+        project,
+        clock.as_ref(),
+    ));
+    unwrap_or_exit(kapacitet::score(&mut roadmap_items, clock.as_ref(), config));
+
+    let report = PlanReport::new(&roadmap_items, &[], clock.today(), config);
+    match args.output {
+        OutputFormat::Csv => {
+            print_config(config, clock.today());
+            let options = CsvOptions {
+                delimiter: args.delimiter,
+                explain: args.explain,
+                ..CsvOptions::default()
             };
+            unwrap_or_exit(report.write_scores_csv(io::stdout(), &options));
         }
-        Err(err) => {
-            error!("Malformed contributor. Make sure that all contributors have the right format! {err}");
-            process::exit(1);
+        OutputFormat::Json => {
+            print_json(&report);
+        }
+        OutputFormat::Html => {
+            unwrap_or_exit(report.write_html(io::stdout()));
+        }
+        OutputFormat::Svg => {
+            unwrap_or_exit(report.write_svg(io::stdout()));
+        }
+        OutputFormat::Mermaid => {
+            unwrap_or_exit(report.write_mermaid(io::stdout()));
+        }
+        OutputFormat::Plantuml => {
+            unwrap_or_exit(report.write_plantuml(io::stdout()));
+        }
+        OutputFormat::Table => {
+            unwrap_or_exit(report.write_table(io::stdout(), colour(), args.explain));
+        }
+        OutputFormat::Markdown => {
+            unwrap_or_exit(report.write_markdown(io::stdout(), args.explain));
         }
     }
 }
 
-fn create_contributor_from_stdin() -> Contributor {
-    let name = parse_string("Contributor name");
-    let seniority = parse_number("Contributor seniority (1-5)", 1, 5);
+fn explain(args: ExplainArgs, config: &Config, project: Option<&Project>) {
+    let clock = clock(args.as_of.or(project.and_then(|project| project.as_of)));
+    let mut roadmap_items = roadmap::open_items(read_roadmap(
+        args.roadmap.as_deref(),
+        args.input_format,
+        project,
+        clock.as_ref(),
+    ));
+    unwrap_or_exit(kapacitet::score(&mut roadmap_items, clock.as_ref(), config));
+
+    if let Some(name) = &args.item {
+        roadmap_items.retain(|roadmap_item| &roadmap_item.name == name);
+        if roadmap_items.is_empty() {
+            error!("There is no roadmap item named {name}");
+            process::exit(1);
+        }
+    }
 
-    return Contributor::new(name, seniority);
+    println!(
+        "Urgency scores calculated with the {0} model as of {1}",
+        config.model,
+        clock.today()
+    );
+    roadmap_items.iter().for_each(|roadmap_item| {
+        if let Some(breakdown) = &roadmap_item.score_breakdown {
+            println!();
+            println!("{0}", roadmap_item.name);
+            println!("{breakdown}");
+        }
+    });
 }
 
-fn create_roadmap_items() -> Vec<RoadmapItem> {
-    println!("Let's add all roadmap items!");
-    let mut roadmap_items: Vec<RoadmapItem> = Vec::new();
-    loop {
-        print!("Do you have a roadmap file? (y/n): ");
-        let mut input = String::new();
-        let _ = io::stdout().flush();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Error reading from STDIN");
-
-        match input.trim_end().to_lowercase().as_str() {
-            "y" => {
-                roadmap_items.append(&mut create_roadmap_items_from_file(read_file_path()));
-                return roadmap_items;
-            }
-            "n" => {
-                roadmap_items.append(&mut create_roadmap_items_from_stdin());
-                return roadmap_items;
-            }
-            _ => {
-                continue;
-            }
-        }
+// Checks every file before reporting, so that CI shows all problems at once
+fn validate(args: cli::ValidateArgs, config: &Config, project: Option<&Project>) {
+    let clock = clock(args.as_of.or(project.and_then(|project| project.as_of)));
+    let today = clock.today();
+    let mut problems: Vec<Problem> = Vec::new();
+    let mut team = None;
+    let mut roadmap = None;
+    let mut absences = Vec::new();
+    if let Some(project) = project {
+        let file_path = &project.file_path;
+        team = Some(report_validation(
+            file_path,
+            "contributors",
+            project.check_contributors(),
+            &mut problems,
+        ));
+        roadmap = Some(report_validation(
+            file_path,
+            "roadmap items",
+            project.check_roadmap(today),
+            &mut problems,
+        ));
+        absences.extend(report_validation(
+            file_path,
+            "holidays and time off",
+            project.check_calendar(),
+            &mut problems,
+        ));
+    } else if args.contributors.is_none() && args.roadmap.is_none() && args.calendar.is_empty() {
+        error!("Pass the files to validate or create a {PROJECT_FILE} project file");
+        process::exit(1);
+    }
+    let format = |file_path: &str| {
+        return args
+            .input_format
+            .unwrap_or_else(|| InputFormat::detect(file_path));
+    };
+    if let Some(file_path) = &args.contributors {
+        let validation = validation::check_contributors_file(file_path, format(file_path));
+        team = Some(report_validation(
+            file_path,
+            "contributors",
+            validation,
+            &mut problems,
+        ));
+    }
+    if let Some(file_path) = &args.roadmap {
+        let validation = validation::check_roadmap_file(file_path, format(file_path), today);
+        roadmap = Some(report_validation(
+            file_path,
+            "roadmap items",
+            validation,
+            &mut problems,
+        ));
+    }
+    for file_path in &args.calendar {
+        let validation = validation::check_calendar_file(file_path);
+        absences.extend(report_validation(
+            file_path,
+            "holidays and time off",
+            validation,
+            &mut problems,
+        ));
+    }
+
+    // The team and the roadmap as a whole are checked on the records that could be read,
+    // so that CI shows these problems along with the ones of single records
+    let mut plan_problems = Vec::new();
+    let contributors: Vec<Contributor> = team
+        .iter()
+        .flatten()
+        .map(|record| record.value.clone())
+        .collect();
+    if let Some(records) = &team {
+        plan_problems.extend(validation::check_team(
+            &source(args.contributors.as_deref(), project),
+            records,
+            config,
+        ));
+    }
+    if let Some(records) = &roadmap {
+        let calendar = Calendar::new(absences.into_iter().map(|record| record.value).collect());
+        plan_problems.extend(validation::check_roadmap_plan(
+            &source(args.roadmap.as_deref(), project),
+            records,
+            &contributors,
+            &calendar,
+            today,
+            config,
+        ));
+    }
+    for problem in &plan_problems {
+        println!("{problem}");
+    }
+    problems.extend(plan_problems);
+
+    let errors = validation::errors(&problems);
+    if errors > 0 {
+        error!("Validation failed with {errors} error(s)");
+        process::exit(1);
     }
 }
 
-fn create_roadmap_items_from_file(file_path: String) -> Vec<RoadmapItem> {
-    loop {
-        let mut roadmap_items: Vec<RoadmapItem> = Vec::new();
-        match csv::Reader::from_path(file_path) {
-            Ok(mut rdr) => {
-                for roadmap_item_result in rdr.deserialize::<RoadmapItem>() {
-                    roadmap_items.push(create_roadmap_item_from_file(roadmap_item_result));
-                }
+fn diff(args: DiffArgs) {
+    let old_plan = unwrap_or_exit(input::read_plan_file(&args.old, args.delimiter));
+    let new_plan = unwrap_or_exit(input::read_plan_file(&args.new, args.delimiter));
+    let diff = PlanDiff::new(&old_plan, &new_plan);
+    match args.output {
+        DiffFormat::Text => {
+            print!("{diff}");
+        }
+        DiffFormat::Markdown => {
+            print!("{0}", diff.to_markdown());
+        }
+        DiffFormat::Json => match serde_json::to_string_pretty(&diff) {
+            Ok(json) => {
+                println!("{json}");
             }
-            Err(_) => {
-                error!("Unable to read roadmap file. Make sure that it has the right format!");
+            Err(err) => {
+                error!("Unable to serialize the diff: {err}");
                 process::exit(1);
             }
-        };
-        return roadmap_items;
+        },
     }
 }
 
-fn create_roadmap_items_from_stdin() -> Vec<RoadmapItem> {
-    let mut roadmap_items: Vec<RoadmapItem> = Vec::new();
-    println!("Let's create our first roadmap item!");
-    roadmap_items.push(create_roadmap_item_from_stdin());
-    loop {
-        print!("Add another roadmap item? (y/n): ");
-        let mut input = String::new();
-        let _ = io::stdout().flush();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Error reading from STDIN");
-
-        match input.trim_end().to_lowercase().as_str() {
-            "y" => {
-                roadmap_items.push(create_roadmap_item_from_stdin());
-            }
-            "n" => {
-                println!("All roadmap items added!");
-                return roadmap_items;
-            }
-            _ => {
-                continue;
-            }
+// Prints the problems and the number of valid records of a file, and returns the records
+// that could be read
+fn report_validation<T>(
+    file_path: &str,
+    records: &str,
+    validation: Validation<T>,
+    problems: &mut Vec<Problem>,
+) -> Vec<Record<T>> {
+    for problem in &validation.problems {
+        println!("{problem}");
+    }
+    println!("{file_path}: {0} valid {records}", validation.valid);
+    problems.extend(validation.problems);
+    return validation.records;
+}
+
+// Stops planning when the team and the roadmap as a whole have errors
+fn check_plan(
+    contributors: &[Contributor],
+    roadmap_items: &[RoadmapItem],
+    calendar: &Calendar,
+    today: NaiveDate,
+    config: &Config,
+    team_source: &str,
+    roadmap_source: &str,
+) {
+    let team: Vec<Record<Contributor>> = contributors
+        .iter()
+        .map(|contributor| Record::new(contributor.clone(), None))
+        .collect();
+    let roadmap: Vec<Record<RoadmapItem>> = roadmap_items
+        .iter()
+        .map(|item| Record::new(item.clone(), None))
+        .collect();
+    let mut problems = validation::check_team(team_source, &team, config);
+    problems.extend(validation::check_roadmap_plan(
+        roadmap_source,
+        &roadmap,
+        contributors,
+        calendar,
+        today,
+        config,
+    ));
+    for problem in &problems {
+        match problem.severity {
+            Severity::Error => error!("{problem}"),
+            _ => warn!("{problem}"),
         }
     }
+    if validation::errors(&problems) > 0 {
+        process::exit(1);
+    }
 }
 
-fn create_roadmap_item_from_file(
-    roadmap_item_result: Result<RoadmapItem, csv::Error>,
-) -> RoadmapItem {
-    match roadmap_item_result {
-        Ok(mut roadmap_item) => {
-            match roadmap_item.validate() {
-                Ok(_) => {
-                    roadmap_item.update_urgency();
-                    return roadmap_item;
-                }
-                Err(_) => {
-                    error!("Invalid roadmap item {roadmap_item}. Make sure that all contributors have valid values!");
-                    process::exit(1);
-                }
-            };
+// Names the file that records came from, for problems that concern all of them
+fn source(file_path: Option<&str>, project: Option<&Project>) -> String {
+    return file_path
+        .map(String::from)
+        .or(project.map(|project| project.file_path.clone()))
+        .unwrap_or_default();
+}
+
+fn load_project(file_path: Option<&str>, needed: bool) -> Option<Project> {
+    match file_path {
+        Some(file_path) => {
+            return Some(unwrap_or_exit(Project::load(file_path)));
         }
-        Err(err) => {
-            error!("Malformed roadmap item. Make sure that all roadmap items have the right format! {err}");
-            process::exit(1);
+        None if needed && Path::new(PROJECT_FILE).exists() => {
+            info!("Loading project from {PROJECT_FILE}");
+            return Some(unwrap_or_exit(Project::load(PROJECT_FILE)));
+        }
+        None => {
+            return None;
         }
     }
 }
 
-fn create_roadmap_item_from_stdin() -> RoadmapItem {
-    let name = parse_string("Roadmap item name");
-    let estimated_complexity = parse_number("Estimated complexity (1-5)", 1, 5);
-    let estimated_value = parse_number("Estimated value (1-5)", 1, 5);
-    loop {
-        let start_date = parse_date("Start date");
-        let target_date = parse_date("Target date");
-        let date_diff = target_date.signed_duration_since(start_date).num_days();
-        if date_diff.is_negative() {
-            warn!("The target date cannot be before the start date.");
-            continue;
-        }
-
-        let today = chrono::offset::Local::now().naive_utc();
-        if target_date
-            .signed_duration_since(today.date())
-            .num_days()
-            .is_negative()
-        {
-            warn!("The target date cannot be before today.");
-            continue;
-        }
-
-        return RoadmapItem::new(
-            name,
-            estimated_complexity,
-            estimated_value,
-            start_date,
-            target_date,
-            Vec::new(),
-        );
+// Reads the file if given and the project's contributors otherwise
+fn read_contributors(
+    file_path: Option<&str>,
+    format: Option<InputFormat>,
+    project: Option<&Project>,
+) -> Vec<Contributor> {
+    match (file_path, project) {
+        (Some(file_path), _) => {
+            let format = format.unwrap_or_else(|| InputFormat::detect(file_path));
+            return unwrap_or_exit(input::read_contributors_file_as(file_path, format));
+        }
+        (None, Some(project)) => {
+            return unwrap_or_exit(project.read_contributors());
+        }
+        (None, None) => {
+            error!("Pass --contributors or create a {PROJECT_FILE} project file");
+            process::exit(1);
+        }
     }
 }
 
-fn parse_string(text: &'static str) -> String {
-    let mut input = String::new();
-    print!("{text}: ");
-    let _ = io::stdout().flush();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Error reading from STDIN");
-    return String::from(input.trim_end());
+fn read_roadmap(
+    file_path: Option<&str>,
+    format: Option<InputFormat>,
+    project: Option<&Project>,
+    clock: &dyn Clock,
+) -> Vec<RoadmapItem> {
+    match (file_path, project) {
+        (Some(file_path), _) => {
+            let format = format.unwrap_or_else(|| InputFormat::detect(file_path));
+            return unwrap_or_exit(input::read_roadmap_file_as(file_path, format, clock));
+        }
+        (None, Some(project)) => {
+            return unwrap_or_exit(project.read_roadmap(clock));
+        }
+        (None, None) => {
+            error!("Pass --roadmap or create a {PROJECT_FILE} project file");
+            process::exit(1);
+        }
+    }
 }
 
-fn parse_number(text: &'static str, min: usize, max: usize) -> usize {
-    loop {
-        print!("{text}: ");
-        let mut input = String::new();
-        let _ = io::stdout().flush();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Error reading from STDIN");
-        match input.trim_end().parse::<usize>() {
-            Ok(value) => {
-                if value < min || value > max {
-                    warn!("The value must be between {min} and {max}");
-                    continue;
-                }
-                return value;
-            }
-            Err(err) => {
-                warn!("Could not parse number: {err}");
-                continue;
-            }
-        };
+fn read_calendar_files(file_paths: &[String]) -> Calendar {
+    let mut calendar = Calendar::default();
+    for file_path in file_paths {
+        calendar.extend(unwrap_or_exit(input::read_calendar_file(file_path)));
     }
+    return calendar;
 }
 
-fn parse_date(input_text: &'static str) -> NaiveDate {
-    loop {
-        print!("{input_text} (YYYY-mm-dd): ");
-        let mut input = String::new();
-        let _ = io::stdout().flush();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Error reading from STDIN");
-        match NaiveDate::parse_from_str(input.trim_end(), "%Y-%m-%d") {
-            Ok(date) => {
-                return date;
+fn init(args: InitArgs) {
+    let today = SystemClock.today();
+    let files = [
+        (
+            PROJECT_CONFIG_FILE,
+            toml::to_string(&Config::default()).expect("Default config is serializable"),
+        ),
+        (
+            PROJECT_FILE,
+            String::from(
+                "contributors = \"contributors.csv\"\nroadmap = \"roadmap.csv\"\n# calendar = [\"holidays.ics\"]\n# horizon_weeks = 26\n",
+            ),
+        ),
+        (
+            "contributors.csv",
+            String::from(
+                "name,seniority,skills\nContributor Uno,5,rust:4;sql:3\nContributor Dos,2,ios:2\n",
+            ),
+        ),
+        (
+            "roadmap.csv",
+            format!(
+                "name,estimated_complexity,estimated_value,start_date,target_date,required_skills\nMVP,4,5,{0},{1},rust:3\n",
+                today,
+                today + Duration::days(90)
+            ),
+        ),
+    ];
+
+    for (file_name, contents) in files {
+        let file_path = Path::new(args.dir.as_str()).join(file_name);
+        if file_path.exists() && !args.force {
+            error!(
+                "{0} already exists. Use --force to overwrite it!",
+                file_path.display()
+            );
+            process::exit(1);
+        }
+        match fs::write(&file_path, contents) {
+            Ok(_) => {
+                println!("Created {0}", file_path.display());
             }
             Err(err) => {
-                warn!("Could not parse date: {err}");
-                continue;
+                error!("Unable to write {0}: {err}", file_path.display());
+                process::exit(1);
             }
-        };
+        }
     }
 }
 
-fn assign_contributors(
+fn interactive(args: InteractiveArgs, config: &Config) {
+    let clock = clock(args.as_of);
+    let contributors = interactive::create_contributors();
+    let roadmap_items = interactive::create_roadmap_items(clock.as_ref(), config.model);
+    let calendar = read_calendar_files(&args.calendar);
+    let report = plan_report(
+        roadmap_items,
+        contributors,
+        &calendar,
+        clock.as_ref(),
+        config,
+        None,
+    );
+    print_plan(
+        &report,
+        config,
+        args.output,
+        &CsvOptions {
+            delimiter: args.delimiter,
+            layout: args.layout,
+            explain: args.explain,
+        },
+    );
+}
+
+fn plan_report(
     roadmap_items: Vec<RoadmapItem>,
-    contributors: &mut Vec<Contributor>,
-) -> Vec<RoadmapItem> {
-    let mut new_items: Vec<RoadmapItem> = Vec::new();
-    roadmap_items.iter().for_each(|item| {
-        info!("Assigning contributors to item {item}");
-        let mut item_contributors: Vec<Contributor> = Vec::new();
-        if item.get_urgency() >= COMPLEXITY_SECOND_THRESHOLD {
-            match contributors.first() {
-                None => {
-                    debug!("No more contributors to assign");
-                }
-                Some(contributor) => {
-                    item_contributors.push(Contributor::new(
-                        String::from(contributor.name.as_str()),
-                        contributor.seniority,
-                    ));
-                    info!("Assigned contributor {contributor} to item {item}");
-                    contributors.remove(0);
-                }
-            }
-            match contributors.last() {
-                None => {
-                    debug!("No more contributors to assign");
-                }
-                Some(contributor) => {
-                    item_contributors.push(Contributor::new(
-                        String::from(contributor.name.as_str()),
-                        contributor.seniority,
-                    ));
-                    info!("Assigned contributor {contributor} to item {item}");
-                    contributors.remove(contributors.len() - 1);
-                }
-            }
-            match contributors
-                .iter()
-                .enumerate()
-                .choose(&mut rand::thread_rng())
-            {
-                None => {
-                    debug!("No more contributors to assign");
-                }
-                Some((i, _)) => {
-                    let c = &contributors.remove(i);
-                    item_contributors
-                        .push(Contributor::new(String::from(c.name.as_str()), c.seniority));
-                    info!("Assigned contributor {c} to item {item}");
-                }
-            }
-        } else if item.get_urgency() >= COMPLEXITY_FIRST_THRESHOLD {
-            match contributors.first() {
-                None => {
-                    debug!("No more contributors to assign");
-                }
-                Some(contributor) => {
-                    item_contributors.push(Contributor::new(
-                        String::from(contributor.name.as_str()),
-                        contributor.seniority,
-                    ));
-                    info!("Assigned contributor {contributor} to item {item}");
-                    contributors.remove(0);
-                }
-            }
-            match contributors.last() {
-                None => {
-                    debug!("No more contributors to assign");
-                }
-                Some(contributor) => {
-                    item_contributors.push(Contributor::new(
-                        String::from(contributor.name.as_str()),
-                        contributor.seniority,
-                    ));
-                    info!("Assigned contributor {contributor} to item {item}");
-                    contributors.remove(contributors.len() - 1);
-                }
+    contributors: Vec<Contributor>,
+    calendar: &Calendar,
+    clock: &dyn Clock,
+    config: &Config,
+    previous_plan: Option<&[ItemReport]>,
+) -> PlanReport {
+    let scheduled_items = unwrap_or_exit(kapacitet::plan(
+        roadmap_items,
+        contributors.clone(),
+        calendar,
+        clock,
+        config,
+    ));
+
+    let mut report = PlanReport::new(&scheduled_items, &contributors, clock.today(), config);
+    report.reassignments = previous_plan.map(|previous_plan| {
+        stability::reassignments(previous_plan, &scheduled_items, &contributors)
+    });
+    return report;
+}
+
+fn print_plan(
+    report: &PlanReport,
+    config: &Config,
+    output: OutputFormat,
+    csv_options: &CsvOptions,
+) {
+    match output {
+        OutputFormat::Csv => {
+            print_config(config, report.metadata.as_of);
+            if let Some(reassignments) = &report.reassignments {
+                print_reassignments(reassignments, config);
             }
+            unwrap_or_exit(report.write_csv(io::stdout(), csv_options));
+        }
+        OutputFormat::Json => {
+            print_json(report);
+        }
+        OutputFormat::Html => {
+            unwrap_or_exit(report.write_html(io::stdout()));
+        }
+        OutputFormat::Svg => {
+            unwrap_or_exit(report.write_svg(io::stdout()));
+        }
+        OutputFormat::Mermaid => {
+            unwrap_or_exit(report.write_mermaid(io::stdout()));
+        }
+        OutputFormat::Plantuml => {
+            unwrap_or_exit(report.write_plantuml(io::stdout()));
+        }
+        OutputFormat::Table => {
+            unwrap_or_exit(report.write_table(io::stdout(), colour(), csv_options.explain));
+        }
+        OutputFormat::Markdown => {
+            unwrap_or_exit(report.write_markdown(io::stdout(), csv_options.explain));
+        }
+    }
+}
+
+// Explains the changes from the previous plan on stderr, keeping the CSV on stdout plain
+fn print_reassignments(reassignments: &[Reassignment], config: &Config) {
+    eprintln!(
+        "# reassignments: {0} (improvement threshold {1})",
+        reassignments.len(),
+        config.stability.improvement_threshold
+    );
+    for reassignment in reassignments {
+        if reassignment.to.is_empty() {
+            eprintln!(
+                "# {0} left {1}: {2}",
+                reassignment.contributor, reassignment.from, reassignment.reason
+            );
         } else {
-            match contributors
-                .iter()
-                .enumerate()
-                .choose(&mut rand::thread_rng())
-            {
-                None => {
-                    debug!("No more contributors to assign");
-                }
-                Some((i, _)) => {
-                    let c = &contributors.remove(i);
-                    item_contributors
-                        .push(Contributor::new(String::from(c.name.as_str()), c.seniority));
-                    info!("Assigned contributor {c} to item {item}");
-                }
-            }
+            eprintln!(
+                "# {0} left {1} for {2}: {3}",
+                reassignment.contributor,
+                reassignment.from,
+                reassignment.to.join(", "),
+                reassignment.reason
+            );
         }
-        let new_item = RoadmapItem::new(
-            String::from(item.name.as_str()),
-            item.estimated_complexity,
-            item.estimated_value,
-            item.start_date,
-            item.target_date,
-            item_contributors,
-        );
-
-        info!("Finished assigning to roadmap item {new_item}");
-        new_items.push(new_item);
-    });
-    return new_items;
+    }
+}
+
+// Colours only end up in a terminal, and not when NO_COLOR is set
+fn colour() -> bool {
+    return io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+}
+
+fn print_json(report: &PlanReport) {
+    match serde_json::to_string_pretty(report) {
+        Ok(json) => {
+            println!("{json}");
+        }
+        Err(err) => {
+            error!("Unable to serialize the plan: {err}");
+            process::exit(1);
+        }
+    }
 }
 
-fn read_file_path() -> String {
-    print!("Please provide the absolute path to your file: ");
-    let mut input = String::new();
-    let _ = io::stdout().flush();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Error reading from STDIN");
-    return String::from(input.trim_end());
+// Records the values used for scoring on stderr, keeping the CSV on stdout plain
+fn print_config(config: &Config, as_of: NaiveDate) {
+    eprintln!("# as of: {as_of}");
+    config
+        .to_string()
+        .lines()
+        .for_each(|line| eprintln!("# {line}"));
+}
+
+fn clock(as_of: Option<NaiveDate>) -> Box<dyn Clock> {
+    match as_of {
+        Some(date) => {
+            return Box::new(FixedClock(date));
+        }
+        None => {
+            return Box::new(SystemClock);
+        }
+    }
+}
+
+fn unwrap_or_exit<T>(result: Result<T, KapacitetError>) -> T {
+    match result {
+        Ok(value) => {
+            return value;
+        }
+        Err(err) => {
+            error!("{err}");
+            process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,241 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::calendar::{Absence, Calendar};
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::contributor::Contributor;
+use crate::error::KapacitetError;
+use crate::input::{
+    read_calendar_file, read_contributors_file, read_roadmap_file, records_from_toml,
+    validate_contributors, validate_roadmap_items, InputFormat,
+};
+use crate::roadmap::RoadmapItem;
+use crate::validation::{
+    check_calendar_file, check_contributors_file, check_inline_contributors, check_inline_roadmap,
+    check_roadmap_file, Validation,
+};
+
+pub static PROJECT_FILE: &str = "kapacitet.toml";
+
+/// A planning cycle in a single TOML file. The team and the roadmap are either paths to
+/// files, relative to the project file, or listed inline as `[[contributors]]` and
+/// `[[roadmap]]` tables. All other keys are settings that override the config.
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub file_path: String,
+    /// Date to plan as of, defaults to the current date
+    pub as_of: Option<NaiveDate>,
+    /// Paths of the files with holidays and time off
+    pub calendar: Vec<String>,
+    pub settings: toml::Table,
+    contributors: Source,
+    roadmap: Source,
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    File(String),
+    Inline(toml::Value),
+}
+
+impl Project {
+    pub fn load(file_path: &str) -> Result<Project, KapacitetError> {
+        let contents = fs::read_to_string(file_path)
+            .map_err(|err| KapacitetError::UnreadableConfig(String::from(file_path), err))?;
+        return Project::parse(file_path, contents.as_str());
+    }
+
+    fn parse(file_path: &str, contents: &str) -> Result<Project, KapacitetError> {
+        let malformed =
+            |reason: String| KapacitetError::MalformedInput(String::from(file_path), reason);
+        let mut settings: toml::Table = toml::from_str(contents)
+            .map_err(|err| KapacitetError::MalformedConfig(String::from(file_path), err))?;
+        let directory = Path::new(file_path).parent().unwrap_or(Path::new(""));
+        let resolve = |path: &str| directory.join(path).to_string_lossy().into_owned();
+
+        let mut source = |key: &str| -> Result<Source, KapacitetError> {
+            match settings.remove(key) {
+                Some(toml::Value::String(path)) => {
+                    return Ok(Source::File(resolve(path.as_str())));
+                }
+                Some(records @ toml::Value::Array(_)) => {
+                    return Ok(Source::Inline(records));
+                }
+                _ => {
+                    return Err(malformed(format!(
+                        "Set {key} to the path of a file or add [[{key}]] tables"
+                    )));
+                }
+            }
+        };
+        let contributors = source("contributors")?;
+        let roadmap = source("roadmap")?;
+
+        let calendar = match settings.remove("calendar") {
+            None => Vec::new(),
+            Some(toml::Value::String(path)) => vec![resolve(path.as_str())],
+            Some(toml::Value::Array(paths)) => paths
+                .iter()
+                .map(|path| match path.as_str() {
+                    Some(path) => Ok(resolve(path)),
+                    None => Err(malformed(String::from(
+                        "The calendar must list the paths of files",
+                    ))),
+                })
+                .collect::<Result<Vec<String>, KapacitetError>>()?,
+            Some(_) => {
+                return Err(malformed(String::from(
+                    "The calendar must list the paths of files",
+                )));
+            }
+        };
+
+        let as_of = match settings.remove("as_of") {
+            None => None,
+            Some(value) => {
+                // Dates can be written as TOML dates or as text
+                let text = match value {
+                    toml::Value::String(text) => text,
+                    toml::Value::Datetime(datetime) => datetime.to_string(),
+                    value => value.to_string(),
+                };
+                match text.parse::<NaiveDate>() {
+                    Ok(date) => Some(date),
+                    Err(err) => {
+                        return Err(malformed(format!("Could not parse as_of {text}. {err}")));
+                    }
+                }
+            }
+        };
+
+        // Check the settings on their own so that errors point at the project file
+        Config::deserialize(toml::Value::Table(settings.clone()))
+            .map_err(|err| KapacitetError::MalformedConfig(String::from(file_path), err))?;
+
+        return Ok(Project {
+            file_path: String::from(file_path),
+            as_of,
+            calendar,
+            settings,
+            contributors,
+            roadmap,
+        });
+    }
+
+    pub fn read_contributors(&self) -> Result<Vec<Contributor>, KapacitetError> {
+        match &self.contributors {
+            Source::File(file_path) => {
+                return read_contributors_file(file_path);
+            }
+            Source::Inline(records) => {
+                let contributors =
+                    records_from_toml(&self.file_path, records.clone(), "contributors")?;
+                return validate_contributors(contributors);
+            }
+        }
+    }
+
+    pub fn read_roadmap(&self, clock: &dyn Clock) -> Result<Vec<RoadmapItem>, KapacitetError> {
+        match &self.roadmap {
+            Source::File(file_path) => {
+                return read_roadmap_file(file_path, clock);
+            }
+            Source::Inline(records) => {
+                let roadmap_items = records_from_toml(&self.file_path, records.clone(), "roadmap")?;
+                return validate_roadmap_items(roadmap_items, clock.today());
+            }
+        }
+    }
+
+    pub fn read_calendar(&self) -> Result<Calendar, KapacitetError> {
+        let mut calendar = Calendar::default();
+        for file_path in &self.calendar {
+            calendar.extend(read_calendar_file(file_path)?);
+        }
+        return Ok(calendar);
+    }
+
+    /// Checks every contributor instead of stopping at the first problem.
+    pub fn check_contributors(&self) -> Validation<Contributor> {
+        match &self.contributors {
+            Source::File(file_path) => {
+                return check_contributors_file(file_path, InputFormat::detect(file_path));
+            }
+            Source::Inline(records) => {
+                return check_inline_contributors(&self.file_path, records.clone());
+            }
+        }
+    }
+
+    /// Checks every roadmap item instead of stopping at the first problem.
+    pub fn check_roadmap(&self, today: NaiveDate) -> Validation<RoadmapItem> {
+        match &self.roadmap {
+            Source::File(file_path) => {
+                return check_roadmap_file(file_path, InputFormat::detect(file_path), today);
+            }
+            Source::Inline(records) => {
+                return check_inline_roadmap(&self.file_path, records.clone(), today);
+            }
+        }
+    }
+
+    pub fn check_calendar(&self) -> Validation<Absence> {
+        let mut validation = Validation::default();
+        for file_path in &self.calendar {
+            validation.extend(check_calendar_file(file_path));
+        }
+        return validation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde::Deserialize;
+
+    use crate::clock::FixedClock;
+    use crate::config::Config;
+    use crate::error::KapacitetError;
+    use crate::project::Project;
+
+    #[test]
+    fn reads_inline_team_and_roadmap_with_settings() {
+        let project = Project::parse(
+            "plans/kapacitet.toml",
+            "as_of = 2022-10-17\ncalendar = \"holidays.ics\"\nhorizon_weeks = 12\n\n[thresholds]\nfirst = 0.2\n\n[[contributors]]\nname = \"IC1\"\nseniority = 5\n\n[[roadmap]]\nname = \"MVP\"\nestimated_complexity = 4\nestimated_value = 5\nstart_date = 2022-06-01\ntarget_date = 2022-11-01\n",
+        )
+        .unwrap();
+        let as_of = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        assert_eq!(project.as_of, Some(as_of));
+        assert_eq!(project.calendar, vec!["plans/holidays.ics"]);
+        assert_eq!(project.read_contributors().unwrap()[0].name, "IC1");
+        assert_eq!(
+            project.read_roadmap(&FixedClock(as_of)).unwrap()[0].name,
+            "MVP"
+        );
+
+        let config = Config::deserialize(toml::Value::Table(project.settings)).unwrap();
+        assert_eq!(config.horizon_weeks, Some(12));
+        assert_eq!(config.thresholds.first, 0.2);
+        assert_eq!(config.thresholds.second, 0.6);
+    }
+
+    #[test]
+    fn rejects_missing_roadmap_and_unknown_settings() {
+        assert!(matches!(
+            Project::parse("kapacitet.toml", "contributors = \"team.csv\"\n"),
+            Err(KapacitetError::MalformedInput(_, _))
+        ));
+        assert!(matches!(
+            Project::parse(
+                "kapacitet.toml",
+                "contributors = \"team.csv\"\nroadmap = \"roadmap.csv\"\nhorizon = 12\n"
+            ),
+            Err(KapacitetError::MalformedConfig(_, _))
+        ));
+    }
+}
@@ -0,0 +1,560 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Dependencies, Scale, Thresholds, Weights};
+use crate::contributor::Contributor;
+use crate::error::KapacitetError;
+use crate::roadmap::RoadmapItem;
+use crate::schedule::Strategy;
+use crate::scoring::{Model, ScoreBreakdown};
+use crate::skill::{Skill, SkillMatching};
+use crate::stability::Reassignment;
+
+/// Version of the JSON schema of the report. Fields may be added without changing it, but
+/// it is bumped whenever a field is renamed, removed or changes its meaning.
+pub static SCHEMA_VERSION: u32 = 1;
+
+pub static WIDE_CSV_HEADER: [&str; 8] = [
+    "name",
+    "start date",
+    "target date",
+    "urgency (0-1)",
+    "contributors",
+    "scheduled start",
+    "scheduled end",
+    "skill gaps",
+];
+pub static LONG_CSV_HEADER: [&str; 10] = [
+    "name",
+    "start date",
+    "target date",
+    "urgency (0-1)",
+    "contributor",
+    "seniority",
+    "allocation",
+    "scheduled start",
+    "scheduled end",
+    "skill gaps",
+];
+
+/// A plan in the structure of the JSON output, see the README for the schema.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanReport {
+    pub schema_version: u32,
+    pub metadata: Metadata,
+    /// Roadmap items from most to least urgent
+    pub items: Vec<ItemReport>,
+    /// Contributors who aren't assigned to any roadmap item
+    pub unassigned_contributors: Vec<ContributorReport>,
+    /// Contributors who left an item they had in the previous plan, if one was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reassignments: Option<Vec<Reassignment>>,
+}
+
+/// The inputs of the run besides the contributors and roadmap items.
+#[derive(Debug, Clone, Serialize)]
+pub struct Metadata {
+    /// Version of Kapacitet that created the plan
+    pub version: &'static str,
+    pub as_of: NaiveDate,
+    pub model: Model,
+    pub seed: Option<u64>,
+    pub strategy: Strategy,
+    pub skill_matching: SkillMatching,
+    pub horizon_weeks: Option<u32>,
+    pub weights: Weights,
+    pub scale: Scale,
+    pub thresholds: Thresholds,
+    pub dependencies: Dependencies,
+    /// Urgency that moving a contributor off an item they had in the previous plan has to
+    /// gain, per full-time position
+    pub improvement_threshold: f64,
+    /// Path of the previous plan whose assignments were kept, if one was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemReport {
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub target_date: NaiveDate,
+    pub urgency: f64,
+    /// Not read back from earlier plans, as it only explains the urgency
+    #[serde(skip_deserializing)]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    pub scheduled_start: Option<NaiveDate>,
+    pub scheduled_end: Option<NaiveDate>,
+    pub contributors: Vec<ContributorReport>,
+    pub required_skills: Vec<Skill>,
+    pub skill_gaps: Vec<Skill>,
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContributorReport {
+    pub name: String,
+    pub seniority: usize,
+    /// Share of a full-time position, allocated to the item or available when unassigned
+    pub allocation: f64,
+}
+
+/// How assignments are laid out in CSV output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvLayout {
+    /// One row per roadmap item, listing its contributors in a single column
+    #[default]
+    Wide,
+    /// One row per assigned contributor, and one for each item without contributors
+    Long,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub layout: CsvLayout,
+    /// Add columns breaking down the urgency score
+    pub explain: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> CsvOptions {
+        return CsvOptions {
+            delimiter: b',',
+            layout: CsvLayout::Wide,
+            explain: false,
+        };
+    }
+}
+
+impl PlanReport {
+    /// Builds the report of the scheduled roadmap items. `contributors` are all contributors
+    /// the plan was made with, so that the ones left without an item can be listed.
+    pub fn new(
+        roadmap_items: &[RoadmapItem],
+        contributors: &[Contributor],
+        as_of: NaiveDate,
+        config: &Config,
+    ) -> PlanReport {
+        let unassigned_contributors = contributors
+            .iter()
+            .filter(|contributor| {
+                !roadmap_items.iter().any(|item| {
+                    item.get_contributors()
+                        .iter()
+                        .any(|assigned| assigned.name == contributor.name)
+                })
+            })
+            .map(ContributorReport::from)
+            .collect();
+        return PlanReport {
+            schema_version: SCHEMA_VERSION,
+            metadata: Metadata::new(as_of, config),
+            items: roadmap_items.iter().map(ItemReport::from).collect(),
+            unassigned_contributors,
+            reassignments: None,
+        };
+    }
+
+    /// Writes the items as CSV, quoting fields that contain the delimiter, quotes or line
+    /// breaks.
+    pub fn write_csv<W: Write>(
+        &self,
+        writer: W,
+        options: &CsvOptions,
+    ) -> Result<(), KapacitetError> {
+        let mut wtr = csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .from_writer(writer);
+        let header = match options.layout {
+            CsvLayout::Wide => WIDE_CSV_HEADER.to_vec(),
+            CsvLayout::Long => LONG_CSV_HEADER.to_vec(),
+        };
+        let mut header: Vec<String> = header.into_iter().map(String::from).collect();
+        header.extend(self.breakdown_header(options.explain));
+        wtr.write_record(&header)
+            .map_err(KapacitetError::UnwritableOutput)?;
+
+        for item in &self.items {
+            let dates = [
+                item.scheduled_start
+                    .map(|date| date.to_string())
+                    .unwrap_or_default(),
+                item.scheduled_end
+                    .map(|date| date.to_string())
+                    .unwrap_or_default(),
+                join(&item.skill_gaps),
+            ];
+            let mut records: Vec<Vec<String>> = Vec::new();
+            match options.layout {
+                CsvLayout::Wide => {
+                    records.push(vec![format_allocations(&item.contributors)]);
+                }
+                CsvLayout::Long if item.contributors.is_empty() => {
+                    records.push(vec![String::new(), String::new(), String::new()]);
+                }
+                CsvLayout::Long => {
+                    for contributor in &item.contributors {
+                        records.push(vec![
+                            contributor.name.clone(),
+                            contributor.seniority.to_string(),
+                            contributor.allocation.to_string(),
+                        ]);
+                    }
+                }
+            }
+            for assignment in records {
+                let mut record = vec![
+                    item.name.clone(),
+                    item.start_date.to_string(),
+                    item.target_date.to_string(),
+                    item.urgency.to_string(),
+                ];
+                record.extend(assignment);
+                record.extend(dates.iter().cloned());
+                record.extend(breakdown_columns(item, options.explain));
+                wtr.write_record(&record)
+                    .map_err(KapacitetError::UnwritableOutput)?;
+            }
+        }
+        return wtr
+            .flush()
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    /// Writes only the name and urgency of each item as CSV.
+    pub fn write_scores_csv<W: Write>(
+        &self,
+        writer: W,
+        options: &CsvOptions,
+    ) -> Result<(), KapacitetError> {
+        let mut wtr = csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .from_writer(writer);
+        let mut header = vec![String::from("name"), String::from("urgency (0-1)")];
+        header.extend(self.breakdown_header(options.explain));
+        wtr.write_record(&header)
+            .map_err(KapacitetError::UnwritableOutput)?;
+        for item in &self.items {
+            let mut record = vec![item.name.clone(), item.urgency.to_string()];
+            record.extend(breakdown_columns(item, options.explain));
+            wtr.write_record(&record)
+                .map_err(KapacitetError::UnwritableOutput)?;
+        }
+        return wtr
+            .flush()
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    pub(crate) fn breakdown_header(&self, explain: bool) -> Vec<String> {
+        if !explain {
+            return Vec::new();
+        }
+        return self
+            .items
+            .iter()
+            .find_map(|item| item.score_breakdown.as_ref())
+            .map(|breakdown| breakdown.csv_header())
+            .unwrap_or_default();
+    }
+}
+
+pub(crate) fn breakdown_columns(item: &ItemReport, explain: bool) -> Vec<String> {
+    if !explain {
+        return Vec::new();
+    }
+    return item
+        .score_breakdown
+        .as_ref()
+        .map(|breakdown| breakdown.to_csv())
+        .unwrap_or_default();
+}
+
+/// Lists contributors with their allocation like `Ada (50%);Bob (100%)`. Semicolons and
+/// backslashes in names are escaped with a backslash.
+pub fn format_allocations(contributors: &[ContributorReport]) -> String {
+    return contributors
+        .iter()
+        .map(|contributor| {
+            format!(
+                "{0} ({1}%)",
+                contributor.name.replace('\\', "\\\\").replace(';', "\\;"),
+                (contributor.allocation * 100.0).round()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(";");
+}
+
+/// Parses contributors listed by `format_allocations`. The seniority isn't part of the list
+/// and is left at 0.
+pub fn parse_allocations(text: &str) -> Result<Vec<ContributorReport>, String> {
+    let mut contributors: Vec<ContributorReport> = Vec::new();
+    for entry in split_allocations(text)
+        .iter()
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+    {
+        // Names can contain " (" themselves, the allocation is always the last one
+        let Some((name, percent)) = entry
+            .strip_suffix("%)")
+            .and_then(|rest| rest.rsplit_once(" ("))
+        else {
+            return Err(format!("Could not parse the allocation of {entry}"));
+        };
+        match percent.parse::<f64>() {
+            Ok(percent) => contributors.push(ContributorReport {
+                name: unescape(name),
+                seniority: 0,
+                allocation: percent / 100.0,
+            }),
+            Err(err) => {
+                return Err(format!("Could not parse the allocation of {entry}. {err}"));
+            }
+        }
+    }
+    return Ok(contributors);
+}
+
+// Splits at the semicolons that `format_allocations` didn't escape
+fn split_allocations(text: &str) -> Vec<String> {
+    let mut entries = vec![String::new()];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                entries.last_mut().unwrap().push(c);
+                if let Some(escaped) = chars.next() {
+                    entries.last_mut().unwrap().push(escaped);
+                }
+            }
+            ';' => {
+                entries.push(String::new());
+            }
+            _ => {
+                entries.last_mut().unwrap().push(c);
+            }
+        }
+    }
+    return entries;
+}
+
+fn unescape(name: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+    return unescaped;
+}
+
+fn join(skills: &[Skill]) -> String {
+    return skills
+        .iter()
+        .map(|skill| skill.to_string())
+        .collect::<Vec<String>>()
+        .join(";");
+}
+
+impl Metadata {
+    pub fn new(as_of: NaiveDate, config: &Config) -> Metadata {
+        return Metadata {
+            version: env!("CARGO_PKG_VERSION"),
+            as_of,
+            model: config.model,
+            seed: config.seed,
+            strategy: config.strategy,
+            skill_matching: config.skill_matching,
+            horizon_weeks: config.horizon_weeks,
+            weights: config.weights.clone(),
+            scale: config.scale.clone(),
+            thresholds: config.thresholds.clone(),
+            dependencies: config.dependencies.clone(),
+            improvement_threshold: config.stability.improvement_threshold,
+            previous: None,
+        };
+    }
+}
+
+impl From<&RoadmapItem> for ItemReport {
+    fn from(item: &RoadmapItem) -> ItemReport {
+        return ItemReport {
+            name: item.name.clone(),
+            start_date: item.start_date,
+            target_date: item.target_date,
+            urgency: item.get_urgency(),
+            score_breakdown: item.score_breakdown.clone(),
+            scheduled_start: item.scheduled_start,
+            scheduled_end: item.scheduled_end,
+            contributors: item
+                .get_contributors()
+                .iter()
+                .map(ContributorReport::from)
+                .collect(),
+            required_skills: item.required_skills.clone(),
+            skill_gaps: item.get_skill_gaps(),
+            depends_on: item.depends_on.clone(),
+        };
+    }
+}
+
+impl From<&Contributor> for ContributorReport {
+    fn from(contributor: &Contributor) -> ContributorReport {
+        return ContributorReport {
+            name: contributor.name.clone(),
+            seniority: contributor.seniority,
+            allocation: contributor.get_allocation(),
+        };
+    }
+}
+
+impl FromStr for CsvLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CsvLayout, String> {
+        match s.to_lowercase().as_str() {
+            "wide" => Ok(CsvLayout::Wide),
+            "long" => Ok(CsvLayout::Long),
+            _ => Err(format!("Unknown CSV layout {s}. Use one of wide or long")),
+        }
+    }
+}
+
+impl Display for CsvLayout {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CsvLayout::Wide => write!(f, "wide"),
+            CsvLayout::Long => write!(f, "long"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use serde_json::json;
+
+    use crate::config::Config;
+    use crate::contributor::Contributor;
+    use crate::input::read_plan;
+    use crate::report::{
+        format_allocations, parse_allocations, ContributorReport, CsvLayout, CsvOptions, PlanReport,
+    };
+    use crate::roadmap::RoadmapItem;
+    use crate::skill::Skill;
+
+    #[test]
+    fn serializes_items_and_unassigned_contributors() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let lead = Contributor::new(String::from("IC1"), 5);
+        let junior = Contributor::new(String::from("IC2"), 1);
+        let mut item = RoadmapItem::new(
+            String::from("MVP"),
+            1,
+            1,
+            today,
+            today + Duration::days(30),
+            vec![lead.allocate(0.5)],
+        );
+        item.urgency = Some(0.4);
+        item.set_schedule(today, today + Duration::days(9));
+
+        let mut report = PlanReport::new(&[item], &[lead, junior], today, &Config::default());
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["schema_version"], json!(1));
+        assert_eq!(value["metadata"]["as_of"], json!("2022-10-17"));
+        assert_eq!(value["metadata"]["weights"]["value"], json!(0.4));
+        assert_eq!(value["metadata"]["improvement_threshold"], json!(0.1));
+        assert!(value["metadata"].get("previous").is_none());
+        assert_eq!(
+            value["items"][0]["contributors"],
+            json!([{"name": "IC1", "seniority": 5, "allocation": 0.5}])
+        );
+        assert_eq!(value["items"][0]["scheduled_end"], json!("2022-10-26"));
+        assert_eq!(
+            value["unassigned_contributors"],
+            json!([{"name": "IC2", "seniority": 1, "allocation": 1.0}])
+        );
+
+        report.metadata.previous = Some(String::from("last-week.json"));
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["metadata"]["previous"], json!("last-week.json"));
+    }
+
+    #[test]
+    fn round_trips_csv_with_special_characters() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let quoted = Contributor::new(String::from("Ada \"The Lead\", Jr."), 5);
+        let junior = Contributor::new(String::from("Bob"), 1);
+        let mut app = RoadmapItem::new(
+            String::from("Billing, v2\nphase 1"),
+            1,
+            1,
+            today,
+            today + Duration::days(30),
+            vec![quoted.allocate(0.5), junior.allocate(1.0)],
+        );
+        app.urgency = Some(0.4);
+        app.required_skills = vec![Skill::new("ios", 3)];
+        app.set_schedule(today, today + Duration::days(9));
+        let web = RoadmapItem::new(
+            String::from("#1 priority"),
+            1,
+            1,
+            today,
+            today + Duration::days(60),
+            vec![],
+        );
+        let report = PlanReport::new(&[app, web], &[], today, &Config::default());
+
+        for layout in [CsvLayout::Wide, CsvLayout::Long] {
+            let options = CsvOptions {
+                delimiter: b';',
+                layout,
+                explain: false,
+            };
+            let mut output: Vec<u8> = Vec::new();
+            report.write_csv(&mut output, &options).unwrap();
+            let items = read_plan(output.as_slice(), b';').unwrap();
+            assert_eq!(items.len(), 2);
+            assert_eq!(items[0].name, report.items[0].name);
+            assert_eq!(items[0].scheduled_end, report.items[0].scheduled_end);
+            assert_eq!(items[0].skill_gaps, vec![Skill::new("ios", 3)]);
+            let names: Vec<&str> = items[0]
+                .contributors
+                .iter()
+                .map(|contributor| contributor.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["Ada \"The Lead\", Jr.", "Bob"]);
+            assert_eq!(items[0].contributors[0].allocation, 0.5);
+            assert_eq!(items[1].name, "#1 priority");
+            assert!(items[1].contributors.is_empty());
+            assert_eq!(items[1].scheduled_start, None);
+        }
+    }
+
+    #[test]
+    fn round_trips_allocations_of_names_with_separators() {
+        let contributors: Vec<ContributorReport> = ["Ada; Lovelace", "Bob (QA)", "C:\\;"]
+            .iter()
+            .map(|name| ContributorReport {
+                name: String::from(*name),
+                seniority: 0,
+                allocation: 0.5,
+            })
+            .collect();
+
+        let text = format_allocations(&contributors);
+        assert_eq!(text, "Ada\\; Lovelace (50%);Bob (QA) (50%);C:\\\\\\; (50%)");
+        assert_eq!(parse_allocations(&text).unwrap(), contributors);
+    }
+}
@@ -1,29 +1,29 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use chrono::NaiveDate;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use validator::{Validate, ValidationError};
 
+use crate::config::{Scale, Weights};
 use crate::contributor::Contributor;
-
-static MAX_ESTIMATED_COMPLEXITY: f64 = 5.0;
-static MIN_ESTIMATED_COMPLEXITY: f64 = 0.0;
+use crate::dependency::deserialize_names;
+use crate::error::KapacitetError;
+use crate::scoring::{ScoreBreakdown, ScoreComponent, ScoringModel};
+use crate::skill::{deserialize_skills, missing_skills, validate_skills, Skill};
 
 static MAX_COMPLEXITY_SCORE: f64 = 1.0;
 static MIN_COMPLEXITY_SCORE: f64 = 0.0;
 
-static MAX_ESTIMATED_VALUE: f64 = 5.0;
-static MIN_ESTIMATED_VALUE: f64 = 0.0;
-
-static TARGET_DATE_FACTOR: f64 = 0.2;
-static DURATION_FACTOR: f64 = 0.1;
-static COMPLEXITY_FACTOR: f64 = 0.3;
-static VALUE_FACTOR: f64 = 0.4;
-
 #[derive(Debug, Clone, Deserialize, Validate)]
-#[validate(schema(function = "validate_dates", skip_on_field_errors = false))]
+#[validate(schema(
+    function = "validate_dates",
+    arg = "&'v_a NaiveDate",
+    skip_on_field_errors = false
+))]
 pub struct RoadmapItem {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
@@ -35,6 +35,77 @@ pub struct RoadmapItem {
     pub target_date: NaiveDate,
     pub urgency: Option<f64>,
     pub contributors: Option<Vec<Contributor>>,
+    pub scheduled_start: Option<NaiveDate>,
+    pub scheduled_end: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "deserialize_skills")]
+    #[validate(custom = "validate_skills")]
+    pub required_skills: Vec<Skill>,
+    /// Names of the roadmap items that have to be finished before this one can start
+    #[serde(default, deserialize_with = "deserialize_names")]
+    pub depends_on: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_status")]
+    pub status: Status,
+    /// Names of the contributors who keep working on the item while it is in progress
+    #[serde(default, deserialize_with = "deserialize_names")]
+    pub assigned: Vec<String>,
+    // Used by the WSJF model
+    #[validate(range(min = 1, max = 10, message = "Business value must be between 1 and 10"))]
+    pub business_value: Option<usize>,
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "Time criticality must be between 1 and 10"
+    ))]
+    pub time_criticality: Option<usize>,
+    #[validate(range(min = 1, max = 10, message = "Risk reduction must be between 1 and 10"))]
+    pub risk_reduction: Option<usize>,
+    #[validate(range(min = 1, max = 10, message = "Job size must be between 1 and 10"))]
+    pub job_size: Option<usize>,
+    // Used by the RICE model
+    #[validate(range(min = 1, max = 10, message = "Reach must be between 1 and 10"))]
+    pub reach: Option<usize>,
+    #[validate(range(min = 0.25, max = 3.0, message = "Impact must be between 0.25 and 3"))]
+    pub impact: Option<f64>,
+    #[validate(range(min = 0, max = 100, message = "Confidence must be between 0 and 100"))]
+    pub confidence: Option<usize>,
+    #[validate(range(min = 1, max = 10, message = "Effort must be between 1 and 10"))]
+    pub effort: Option<usize>,
+    // Used by the MoSCoW model
+    pub moscow: Option<Moscow>,
+    #[serde(skip)]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    /// Names of the contributors on the item in the previous plan
+    #[serde(skip)]
+    pub previous: Vec<String>,
+    /// Why the assigner moved contributors here from items they had in the previous plan,
+    /// by the names of the contributor and the item they left
+    #[serde(skip)]
+    pub move_reasons: HashMap<(String, String), String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    #[default]
+    Planned,
+    /// Keeps the contributors assigned to it
+    #[serde(alias = "in progress", alias = "in-progress")]
+    InProgress,
+    /// Is left out of the plan
+    Done,
+    /// Is left out of the plan
+    #[serde(alias = "canceled")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Moscow {
+    Must,
+    Should,
+    Could,
+    #[serde(alias = "won't")]
+    Wont,
 }
 
 impl RoadmapItem {
@@ -52,75 +123,175 @@ impl RoadmapItem {
             estimated_value,
             start_date,
             target_date,
-            urgency: Some(calculate_project_urgency(
-                estimated_complexity,
-                estimated_value,
-                start_date,
-                target_date,
-            )),
+            urgency: None,
             contributors: Some(contributors),
+            scheduled_start: None,
+            scheduled_end: None,
+            required_skills: Vec::new(),
+            depends_on: Vec::new(),
+            status: Status::Planned,
+            assigned: Vec::new(),
+            business_value: None,
+            time_criticality: None,
+            risk_reduction: None,
+            job_size: None,
+            reach: None,
+            impact: None,
+            confidence: None,
+            effort: None,
+            moscow: None,
+            score_breakdown: None,
+            previous: Vec::new(),
+            move_reasons: HashMap::new(),
         };
     }
 
     pub fn get_urgency(&self) -> f64 {
-        self.urgency.unwrap_or_else(|| 0.0)
+        self.urgency.unwrap_or(0.0)
     }
 
     pub fn get_contributors(&self) -> Vec<Contributor> {
-        self.contributors.clone().unwrap_or_else(|| Vec::new())
+        self.contributors.clone().unwrap_or_default()
     }
 
-    pub fn update_urgency(&mut self) {
-        self.urgency = Some(calculate_project_urgency(
-            self.estimated_complexity,
-            self.estimated_value,
-            self.start_date,
-            self.target_date,
-        ));
+    /// The required skills that no assigned contributor has at the required level.
+    pub fn get_skill_gaps(&self) -> Vec<Skill> {
+        return missing_skills(&self.required_skills, &self.get_contributors());
+    }
+
+    pub fn update_urgency(
+        &mut self,
+        model: &dyn ScoringModel,
+        today: NaiveDate,
+    ) -> Result<(), KapacitetError> {
+        let breakdown = model.explain(self, today)?;
+        self.urgency = Some(breakdown.score);
+        self.score_breakdown = Some(breakdown);
+        return Ok(());
+    }
+
+    /// Raises the urgency to the share inherited from an item waiting on this one.
+    pub fn inherit_urgency(&mut self, urgency: f64, from: &str) {
+        self.urgency = Some(urgency);
+        if let Some(breakdown) = self.score_breakdown.as_mut() {
+            breakdown.score = urgency;
+            breakdown.inherited_from = Some(String::from(from));
+        }
+    }
+
+    pub fn set_schedule(&mut self, scheduled_start: NaiveDate, scheduled_end: NaiveDate) {
+        self.scheduled_start = Some(scheduled_start);
+        self.scheduled_end = Some(scheduled_end);
+    }
+
+    /// Whether the item still needs to be planned, i.e. it is neither done nor cancelled.
+    pub fn is_open(&self) -> bool {
+        return !matches!(self.status, Status::Done | Status::Cancelled);
     }
 }
 
-fn calculate_project_urgency(
+/// Leaves out the items that are done or cancelled. Open items no longer wait for them.
+pub fn open_items(roadmap_items: Vec<RoadmapItem>) -> Vec<RoadmapItem> {
+    let (open_items, closed_items): (Vec<RoadmapItem>, Vec<RoadmapItem>) =
+        roadmap_items.into_iter().partition(RoadmapItem::is_open);
+    return open_items
+        .into_iter()
+        .map(|mut item| {
+            item.depends_on
+                .retain(|name| !closed_items.iter().any(|closed| closed.name == *name));
+            item
+        })
+        .collect();
+}
+
+pub fn calculate_project_urgency(
     estimated_complexity: usize,
     estimated_value: usize,
     start_date: NaiveDate,
     target_date: NaiveDate,
+    today: NaiveDate,
+    weights: &Weights,
+    scale: &Scale,
 ) -> f64 {
+    return explain_project_urgency(
+        estimated_complexity,
+        estimated_value,
+        start_date,
+        target_date,
+        today,
+        weights,
+        scale,
+    )
+    .score;
+}
+
+pub fn explain_project_urgency(
+    estimated_complexity: usize,
+    estimated_value: usize,
+    start_date: NaiveDate,
+    target_date: NaiveDate,
+    today: NaiveDate,
+    weights: &Weights,
+    scale: &Scale,
+) -> ScoreBreakdown {
     // 1. Target date - the closer in time the more urgent
-    let today = chrono::offset::Local::now().naive_utc();
-    let days_from_today =
-        TARGET_DATE_FACTOR / target_date.signed_duration_since(today.date()).num_days() as f64;
+    let days_until_target = target_date.signed_duration_since(today).num_days() as f64;
+    let days_from_today = weigh_days(weights.target_date, days_until_target);
     debug!("Days from today: {days_from_today}");
 
     // 2. Duration - the shorter the more urgent
-    let project_duration =
-        DURATION_FACTOR / target_date.signed_duration_since(start_date).num_days() as f64;
+    let duration_days = target_date.signed_duration_since(start_date).num_days() as f64;
+    let project_duration = weigh_days(weights.duration, duration_days);
     debug!("Duration: {project_duration}");
 
     // 3. Estimated complexity - the higher the more urgent
-    let complexity = (estimated_complexity as f64 - MIN_ESTIMATED_COMPLEXITY)
-        / (MAX_ESTIMATED_COMPLEXITY - MIN_ESTIMATED_COMPLEXITY)
-        * COMPLEXITY_FACTOR;
+    let complexity = (estimated_complexity as f64 - scale.min_estimated_complexity)
+        / (scale.max_estimated_complexity - scale.min_estimated_complexity)
+        * weights.complexity;
     debug!("Complexity: {complexity}");
 
     // Estimated value - the more value added the more urgent
-    let value = (estimated_value as f64 - MIN_ESTIMATED_VALUE)
-        / (MAX_ESTIMATED_VALUE - MIN_ESTIMATED_VALUE)
-        * VALUE_FACTOR;
+    let value = (estimated_value as f64 - scale.min_estimated_value)
+        / (scale.max_estimated_value - scale.min_estimated_value)
+        * weights.value;
     debug!("Value: {value}");
 
-    let total = days_from_today + project_duration + complexity + value;
-    debug!("Total: {total}");
+    let breakdown = ScoreBreakdown::new(
+        vec![
+            ScoreComponent::new(
+                "target date",
+                days_until_target,
+                weights.target_date,
+                days_from_today,
+            ),
+            ScoreComponent::new(
+                "duration",
+                duration_days,
+                weights.duration,
+                project_duration,
+            ),
+            ScoreComponent::new(
+                "complexity",
+                estimated_complexity as f64,
+                weights.complexity,
+                complexity,
+            ),
+            ScoreComponent::new("value", estimated_value as f64, weights.value, value),
+        ],
+        |total| (total - MIN_COMPLEXITY_SCORE) / (MAX_COMPLEXITY_SCORE - MIN_COMPLEXITY_SCORE),
+    );
+    debug!("Total: {0}", breakdown.total);
+
+    return breakdown;
+}
 
-    (f64::max(
-        0.0,
-        f64::min(
-            1.0,
-            (total - MIN_COMPLEXITY_SCORE) / (MAX_COMPLEXITY_SCORE - MIN_COMPLEXITY_SCORE),
-        ),
-    ) * 100.0)
-        .round()
-        / 100.0
+// Fewer days are more urgent. A component weighted 0 counts for nothing, even when the days
+// are 0 too, which would otherwise make the score NaN.
+fn weigh_days(weight: f64, days: f64) -> f64 {
+    if weight == 0.0 {
+        return 0.0;
+    }
+    return weight / days;
 }
 
 impl Ord for RoadmapItem {
@@ -167,20 +338,71 @@ impl Display for RoadmapItem {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "{0},{1},{2},{3},{4}",
+            "{0},{1},{2},{3},{4},{5},{6},{7}",
             self.name,
             self.start_date,
             self.target_date,
-            self.urgency.unwrap_or_else(|| 0.0),
+            self.urgency.unwrap_or(0.0),
             self.get_contributors()
                 .iter()
-                .map(|contributor| format!("{}", contributor.name))
-                .fold(String::new(), |acc, arg| acc + arg.as_str() + ";")
+                .map(|contributor| format!(
+                    "{0} ({1}%)",
+                    contributor.name,
+                    (contributor.get_allocation() * 100.0).round()
+                ))
+                .collect::<Vec<String>>()
+                .join(";"),
+            self.scheduled_start
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+            self.scheduled_end
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+            self.get_skill_gaps()
+                .iter()
+                .map(|skill| skill.to_string())
+                .collect::<Vec<String>>()
+                .join(";")
         )
     }
 }
 
-fn validate_dates(item: &RoadmapItem) -> Result<(), ValidationError> {
+// An empty status means the item is planned
+fn deserialize_status<'de, D>(deserializer: D) -> Result<Status, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    return Ok(Option::<Status>::deserialize(deserializer)?.unwrap_or_default());
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Status, String> {
+        match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "planned" => Ok(Status::Planned),
+            "in_progress" => Ok(Status::InProgress),
+            "done" => Ok(Status::Done),
+            "cancelled" | "canceled" => Ok(Status::Cancelled),
+            _ => Err(format!(
+                "Unknown status {s}. Use one of planned, in_progress, done or cancelled"
+            )),
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Status::Planned => write!(f, "planned"),
+            Status::InProgress => write!(f, "in_progress"),
+            Status::Done => write!(f, "done"),
+            Status::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+fn validate_dates(item: &RoadmapItem, today: &NaiveDate) -> Result<(), ValidationError> {
     let date_diff = item
         .target_date
         .signed_duration_since(item.start_date)
@@ -191,12 +413,13 @@ fn validate_dates(item: &RoadmapItem) -> Result<(), ValidationError> {
         ));
     }
 
-    let today = chrono::offset::Local::now().naive_utc();
-    if item
-        .target_date
-        .signed_duration_since(today.date())
-        .num_days()
-        .is_negative()
+    // Finished and cancelled items may well be in the past
+    if item.is_open()
+        && item
+            .target_date
+            .signed_duration_since(*today)
+            .num_days()
+            .is_negative()
     {
         return Err(ValidationError::new(
             "The target date cannot be before today.",
@@ -211,16 +434,53 @@ fn validate_dates(item: &RoadmapItem) -> Result<(), ValidationError> {
 mod tests {
     use chrono::NaiveDate;
 
-    use crate::roadmap::calculate_project_urgency;
+    use crate::clock::{Clock, FixedClock};
+    use crate::config::{Scale, Weights};
+    use crate::roadmap::{calculate_project_urgency, explain_project_urgency};
 
     #[test]
     fn it_works() {
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2022, 10, 15).unwrap());
         let result = calculate_project_urgency(
             5,
             5,
             NaiveDate::from_ymd_opt(2022, 10, 15).unwrap(),
             NaiveDate::from_ymd_opt(2022, 10, 16).unwrap(),
+            clock.today(),
+            &Weights::default(),
+            &Scale::default(),
         );
-        println!("{result}")
+        println!("{result}");
+        assert_eq!(result, 1.0);
+
+        let result = calculate_project_urgency(
+            3,
+            2,
+            NaiveDate::from_ymd_opt(2022, 9, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
+            clock.today(),
+            &Weights::default(),
+            &Scale::default(),
+        );
+        println!("{result}");
+        assert_eq!(result, 0.34);
+    }
+
+    #[test]
+    fn ignores_days_weighted_zero() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+        let weights = Weights {
+            target_date: 0.0,
+            duration: 0.0,
+            complexity: 0.5,
+            value: 0.5,
+        };
+        // Due today and without any duration
+        let breakdown =
+            explain_project_urgency(5, 5, today, today, today, &weights, &Scale::default());
+        assert_eq!(breakdown.components[0].contribution, 0.0);
+        assert_eq!(breakdown.components[1].contribution, 0.0);
+        assert!(!breakdown.score.is_nan());
+        assert!(!breakdown.clamped);
     }
 }
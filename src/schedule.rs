@@ -0,0 +1,828 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate};
+use rand::prelude::IteratorRandom;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::Calendar;
+use crate::clock::Clock;
+use crate::config::{Config, UrgencyBand};
+use crate::contributor::Contributor;
+use crate::roadmap::{RoadmapItem, Status};
+use crate::skill::{missing_skills, SkillMatching};
+use crate::solver::assign_contributors_optimally;
+
+// One complexity point equals two working weeks of effort for a single contributor
+static WORKING_DAYS_PER_COMPLEXITY_POINT: f64 = 10.0;
+
+/// Most contributors assigned to a single roadmap item.
+pub static MAX_HEADCOUNT: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    /// Picks the most junior, the most senior and a random contributor one item at a time
+    Greedy,
+    /// Solves for the assignment with the best urgency-weighted coverage of all items
+    #[default]
+    Optimal,
+}
+
+// A share of a contributor's capacity that is free from the given date
+struct Availability {
+    contributor: Contributor,
+    free_from: NaiveDate,
+}
+
+// Tolerance for rounding when adding up and subtracting capacity
+pub static CAPACITY_EPSILON: f64 = 0.001;
+
+pub fn schedule_roadmap_items(
+    roadmap_items: Vec<RoadmapItem>,
+    contributors: Vec<Contributor>,
+    calendar: &Calendar,
+    clock: &dyn Clock,
+    config: &Config,
+    rng: &mut dyn RngCore,
+) -> Vec<RoadmapItem> {
+    let today = clock.today();
+    let horizon_end = config.horizon_end(today);
+    let mut timeline: Vec<Availability> = contributors
+        .into_iter()
+        .map(|contributor| Availability {
+            free_from: calendar.next_working_day(NaiveDate::max(
+                today,
+                contributor.available_from.unwrap_or(today),
+            )),
+            contributor,
+        })
+        .collect();
+    // Contributors keep working on the items in progress they are assigned to, before the
+    // rest of their capacity is distributed
+    let (pinned_items, mut pending_items): (Vec<RoadmapItem>, Vec<RoadmapItem>) = roadmap_items
+        .into_iter()
+        .partition(|item| item.status == Status::InProgress && !item.assigned.is_empty());
+    let (mut scheduled_items, unstaffed_items) =
+        schedule_pinned_items(pinned_items, &mut timeline, calendar, today);
+    pending_items.extend(unstaffed_items);
+    pending_items.sort();
+
+    while !pending_items.is_empty() {
+        let wave_date = match timeline.iter().map(|a| a.free_from).min() {
+            None => {
+                warn!("There are no contributors to schedule the roadmap items with");
+                break;
+            }
+            Some(date) => date,
+        };
+        if horizon_end.is_some_and(|end| wave_date > end) {
+            info!(
+                "Leaving {0} roadmap items unscheduled beyond the horizon",
+                pending_items.len()
+            );
+            break;
+        }
+
+        let mut available_contributors: Vec<Contributor> = Vec::new();
+        timeline.retain(|availability| {
+            if availability.free_from > wave_date {
+                return true;
+            }
+            if !availability.contributor.is_employed_on(wave_date) {
+                debug!("{0} is no longer available", availability.contributor);
+                return false;
+            }
+            // Capacity freed up by different items is pooled again
+            match available_contributors
+                .iter_mut()
+                .find(|contributor| contributor.name == availability.contributor.name)
+            {
+                None => available_contributors.push(availability.contributor.clone()),
+                Some(contributor) => contributor.capacity += availability.contributor.capacity,
+            }
+            return false;
+        });
+        available_contributors.sort();
+        debug!(
+            "Scheduling wave starting {wave_date} with {0} contributors",
+            available_contributors.len()
+        );
+
+        // Items can only be staffed once everything they depend on has been finished, and
+        // not at all when they start beyond the horizon
+        let (ready_items, blocked_items): (Vec<RoadmapItem>, Vec<RoadmapItem>) =
+            pending_items.into_iter().partition(|item| {
+                horizon_end.is_none_or(|end| item.start_date <= end)
+                    && item.depends_on.iter().all(|name| {
+                        scheduled_items.iter().any(|scheduled| {
+                            scheduled.name == *name
+                                && scheduled.scheduled_end.is_some_and(|end| end < wave_date)
+                        })
+                    })
+            });
+
+        let scheduled_before = scheduled_items.len();
+        let assigned_items = match config.strategy {
+            Strategy::Greedy => {
+                assign_contributors(ready_items, &mut available_contributors, config, rng)
+            }
+            Strategy::Optimal => {
+                assign_contributors_optimally(ready_items, &mut available_contributors, config)
+            }
+        };
+        pending_items = blocked_items;
+        for mut item in assigned_items {
+            let item_contributors = item.get_contributors();
+            if item_contributors.is_empty() {
+                pending_items.push(item);
+                continue;
+            }
+
+            let start = calendar.next_working_day(NaiveDate::max(wave_date, item.start_date));
+            let end = calendar.finish_date(
+                start,
+                estimate_effort(item.estimated_complexity),
+                &item_contributors,
+            );
+            info!("Scheduled roadmap item {item} from {start} to {end}");
+            item.set_schedule(start, end);
+            item_contributors.into_iter().for_each(|mut contributor| {
+                contributor.capacity = contributor.get_allocation();
+                contributor.allocation = None;
+                timeline.push(Availability {
+                    contributor,
+                    free_from: calendar.next_working_day(end + Duration::days(1)),
+                })
+            });
+            scheduled_items.push(item);
+        }
+        pending_items.sort();
+
+        // Contributors are left over once every item has been staffed, or when they lack
+        // the skills or the dependencies of the remaining items aren't finished yet. Then
+        // they wait for someone to free up.
+        let mut free_from = wave_date;
+        if scheduled_items.len() == scheduled_before {
+            match timeline.iter().map(|a| a.free_from).min() {
+                None => {
+                    warn!(
+                        "Unable to staff {0} roadmap items with the available contributors",
+                        pending_items.len()
+                    );
+                    break;
+                }
+                Some(date) => free_from = date,
+            }
+        }
+        available_contributors.into_iter().for_each(|contributor| {
+            timeline.push(Availability {
+                contributor,
+                free_from,
+            })
+        });
+    }
+
+    scheduled_items.append(&mut pending_items);
+    scheduled_items.sort();
+    return scheduled_items;
+}
+
+// Staffs the items in progress with the contributors assigned to them. The capacity of each
+// contributor is split evenly across the items they are pinned to.
+fn schedule_pinned_items(
+    pinned_items: Vec<RoadmapItem>,
+    timeline: &mut Vec<Availability>,
+    calendar: &Calendar,
+    today: NaiveDate,
+) -> (Vec<RoadmapItem>, Vec<RoadmapItem>) {
+    let mut pins: HashMap<String, usize> = HashMap::new();
+    for name in pinned_items.iter().flat_map(|item| item.assigned.iter()) {
+        *pins.entry(name.clone()).or_default() += 1;
+    }
+    let shares: HashMap<String, f64> = pins
+        .into_iter()
+        .filter_map(|(name, count)| {
+            timeline
+                .iter()
+                .find(|availability| availability.contributor.name == name)
+                .map(|availability| (name, availability.contributor.capacity / count as f64))
+        })
+        .collect();
+
+    let mut scheduled_items = Vec::new();
+    let mut unstaffed_items = Vec::new();
+    for mut item in pinned_items {
+        let mut team: Vec<Contributor> = Vec::new();
+        for name in &item.assigned {
+            let availability = timeline
+                .iter_mut()
+                .find(|availability| availability.contributor.name == *name);
+            match (availability, shares.get(name)) {
+                (Some(availability), Some(share)) => {
+                    availability.contributor.capacity -= share;
+                    team.push(availability.contributor.allocate(*share));
+                }
+                _ => warn!(
+                    "{name} is assigned to {0}, but is not on the team",
+                    item.name
+                ),
+            }
+        }
+        timeline.retain(|availability| availability.contributor.capacity > CAPACITY_EPSILON);
+        if team.is_empty() {
+            unstaffed_items.push(item);
+            continue;
+        }
+
+        // The work started before today, so only its end is estimated
+        let start = calendar.next_working_day(NaiveDate::min(item.start_date, today));
+        let end = NaiveDate::max(
+            calendar.finish_date(start, estimate_effort(item.estimated_complexity), &team),
+            calendar.next_working_day(today),
+        );
+        info!("Keeping roadmap item {item} in progress until {end}");
+        item.contributors = Some(team.clone());
+        item.set_schedule(start, end);
+        team.into_iter().for_each(|mut contributor| {
+            contributor.capacity = contributor.get_allocation();
+            contributor.allocation = None;
+            timeline.push(Availability {
+                contributor,
+                free_from: calendar.next_working_day(end + Duration::days(1)),
+            })
+        });
+        scheduled_items.push(item);
+    }
+    return (scheduled_items, unstaffed_items);
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Strategy, String> {
+        match s.to_lowercase().as_str() {
+            "greedy" => Ok(Strategy::Greedy),
+            "optimal" => Ok(Strategy::Optimal),
+            _ => Err(format!(
+                "Unknown strategy {s}. Use one of greedy or optimal"
+            )),
+        }
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Strategy::Greedy => write!(f, "greedy"),
+            Strategy::Optimal => write!(f, "optimal"),
+        }
+    }
+}
+
+/// Estimates the working days an item takes for a single full-time contributor.
+pub fn estimate_effort(estimated_complexity: usize) -> f64 {
+    return estimated_complexity as f64 * WORKING_DAYS_PER_COMPLEXITY_POINT;
+}
+
+/// Estimates the working days an item takes with the given team capacity in FTE, when
+/// nobody takes time off.
+pub fn estimate_duration(estimated_complexity: usize, team_capacity: f64) -> i64 {
+    let effort = estimate_effort(estimated_complexity);
+    if team_capacity < CAPACITY_EPSILON {
+        return effort.ceil() as i64;
+    }
+    return (effort / team_capacity - CAPACITY_EPSILON).ceil() as i64;
+}
+
+/// How many contributors a roadmap item should get, depending on the urgency thresholds.
+pub fn headcount(item: &RoadmapItem, config: &Config) -> usize {
+    match config.thresholds.band(item.get_urgency()) {
+        UrgencyBand::High => {
+            return MAX_HEADCOUNT;
+        }
+        UrgencyBand::Medium => {
+            return 2;
+        }
+        UrgencyBand::Low => {
+            return 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Pick {
+    First,
+    Last,
+    Random,
+}
+
+pub fn assign_contributors(
+    roadmap_items: Vec<RoadmapItem>,
+    contributors: &mut Vec<Contributor>,
+    config: &Config,
+    rng: &mut dyn RngCore,
+) -> Vec<RoadmapItem> {
+    // Contributors are kept for the items they had in the previous plan until these are
+    // staffed
+    let mut waiting: Vec<&RoadmapItem> = roadmap_items.iter().collect();
+    let mut new_items: Vec<RoadmapItem> = Vec::new();
+    roadmap_items.iter().for_each(|item| {
+        info!("Assigning contributors to item {item}");
+        let limit = headcount(item, config);
+        let picks = match limit {
+            3 => vec![Pick::First, Pick::Last, Pick::Random],
+            2 => vec![Pick::First, Pick::Last],
+            _ => vec![Pick::Random],
+        };
+
+        let mut item_contributors: Vec<Contributor> = Vec::new();
+        for pick in picks {
+            match pick_contributor(
+                item,
+                &item_contributors,
+                contributors,
+                &waiting,
+                pick,
+                config,
+                rng,
+            ) {
+                None => {
+                    debug!("No more contributors to assign");
+                }
+                Some(contributor) => {
+                    info!("Assigned contributor {contributor} to item {item}");
+                    item_contributors.push(contributor);
+                }
+            }
+        }
+
+        if config.skill_matching == SkillMatching::Require && !item.required_skills.is_empty() {
+            // Keep adding skilled contributors until every required skill is covered, but not
+            // beyond the headcount of the item, like the optimal strategy
+            while !missing_skills(&item.required_skills, &item_contributors).is_empty()
+                && item_contributors.len() < limit
+            {
+                match pick_contributor(
+                    item,
+                    &item_contributors,
+                    contributors,
+                    &waiting,
+                    Pick::First,
+                    config,
+                    rng,
+                ) {
+                    None => break,
+                    Some(contributor) => {
+                        info!("Assigned contributor {contributor} to item {item}");
+                        item_contributors.push(contributor);
+                    }
+                }
+            }
+            if !missing_skills(&item.required_skills, &item_contributors).is_empty() {
+                if item_contributors.len() < limit {
+                    info!("Not enough skilled contributors available for item {item}");
+                } else {
+                    info!(
+                        "The required skills of item {item} can't be covered by its headcount of {limit}"
+                    );
+                }
+                contributors.append(&mut item_contributors);
+                contributors.sort();
+            }
+        }
+
+        let mut new_item = item.clone();
+        for contributor in &item_contributors {
+            for previous in left_for(item, &contributor.name, &waiting) {
+                new_item.move_reasons.insert(
+                    (contributor.name.clone(), previous.name.clone()),
+                    format!(
+                        "{0} is more urgent ({1:.2} over {2:.2}) by more than the improvement threshold",
+                        item.name,
+                        item.get_urgency(),
+                        previous.get_urgency()
+                    ),
+                );
+            }
+        }
+        new_item.contributors = Some(item_contributors);
+        waiting.retain(|other| other.name != item.name);
+
+        info!("Finished assigning to roadmap item {new_item}");
+        new_items.push(new_item);
+    });
+    return new_items;
+}
+
+// The items still waiting that the contributor would leave by joining the item
+fn left_for<'a>(
+    item: &RoadmapItem,
+    name: &String,
+    waiting: &[&'a RoadmapItem],
+) -> Vec<&'a RoadmapItem> {
+    if item.previous.contains(name) {
+        return Vec::new();
+    }
+    return waiting
+        .iter()
+        .filter(|other| other.name != item.name && other.previous.contains(name))
+        .copied()
+        .collect();
+}
+
+// Picks among the contributors who cover a required skill that the team is still missing.
+// When preferring skills, anyone can be picked if nobody covers a missing skill. Contributors
+// kept for items they had in the previous plan are only picked for items that are more
+// urgent by more than the improvement threshold.
+fn pick_contributor(
+    item: &RoadmapItem,
+    team: &[Contributor],
+    contributors: &mut Vec<Contributor>,
+    waiting: &[&RoadmapItem],
+    pick: Pick,
+    config: &Config,
+    rng: &mut dyn RngCore,
+) -> Option<Contributor> {
+    let free: Vec<usize> = (0..contributors.len())
+        .filter(|i| {
+            return left_for(item, &contributors[*i].name, waiting)
+                .iter()
+                .all(|previous| {
+                    item.get_urgency() - previous.get_urgency()
+                        > config.stability.improvement_threshold
+                });
+        })
+        .collect();
+    let missing = missing_skills(&item.required_skills, team);
+    let mut candidates: Vec<usize> = free
+        .iter()
+        .copied()
+        .filter(|i| {
+            missing
+                .iter()
+                .any(|skill| contributors[*i].has_skill(skill))
+        })
+        .collect();
+    if candidates.is_empty() {
+        match config.skill_matching {
+            SkillMatching::Require if !item.required_skills.is_empty() => {
+                // Extra contributors still need at least one of the required skills
+                candidates = free
+                    .iter()
+                    .copied()
+                    .filter(|i| {
+                        item.required_skills
+                            .iter()
+                            .any(|skill| contributors[*i].has_skill(skill))
+                    })
+                    .collect();
+            }
+            _ => {
+                candidates = free;
+            }
+        }
+    }
+
+    // Contributors stay on the item they had in the previous plan while they can
+    let previous = candidates
+        .iter()
+        .position(|i| item.previous.contains(&contributors[*i].name));
+    if let Some(position) = previous {
+        return Some(contributors.remove(candidates[position]));
+    }
+
+    let index = match pick {
+        Pick::First => candidates.first().copied(),
+        Pick::Last => candidates.last().copied(),
+        Pick::Random => candidates.into_iter().choose(rng),
+    }?;
+    return Some(contributors.remove(index));
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, Duration, NaiveDate, Weekday};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::calendar::{Absence, Calendar};
+    use crate::clock::FixedClock;
+    use crate::config::Config;
+    use crate::contributor::Contributor;
+    use crate::roadmap::{open_items, RoadmapItem, Status};
+    use crate::schedule::{assign_contributors, estimate_duration, schedule_roadmap_items};
+    use crate::skill::{Skill, SkillMatching};
+
+    #[test]
+    fn estimates_duration_from_complexity_and_team_size() {
+        assert_eq!(estimate_duration(1, 1.0), 10);
+        assert_eq!(estimate_duration(5, 1.0), 50);
+        assert_eq!(estimate_duration(5, 3.0), 17);
+        assert_eq!(estimate_duration(2, 0.0), 20);
+        assert_eq!(estimate_duration(1, 0.5), 20);
+        assert_eq!(estimate_duration(2, 1.5), 14);
+    }
+
+    #[test]
+    fn schedules_items_after_contributors_free_up() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let start_date = today - Duration::days(30);
+        let target_date = today + Duration::days(365);
+        let mut items = vec![
+            RoadmapItem::new(String::from("one"), 1, 1, start_date, target_date, vec![]),
+            RoadmapItem::new(String::from("two"), 1, 1, start_date, target_date, vec![]),
+        ];
+        let config = Config::default();
+        let model = config.model.build(&config);
+        items
+            .iter_mut()
+            .for_each(|item| item.update_urgency(model.as_ref(), today).unwrap());
+        let contributors = vec![Contributor::new(String::from("IC1"), 3)];
+
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let scheduled = schedule_roadmap_items(
+            items,
+            contributors,
+            &Calendar::default(),
+            &FixedClock(today),
+            &config,
+            &mut rng,
+        );
+
+        assert_eq!(scheduled.len(), 2);
+        assert!(scheduled
+            .iter()
+            .all(|item| item.get_contributors().len() == 1));
+        let mut ends: Vec<(NaiveDate, NaiveDate)> = scheduled
+            .iter()
+            .map(|item| (item.scheduled_start.unwrap(), item.scheduled_end.unwrap()))
+            .collect();
+        ends.sort();
+        assert_eq!(ends[0].0, today);
+        assert_eq!(ends[0].1, Calendar::default().add_working_days(today, 10));
+        assert!(ends[1].0 > ends[0].1);
+        assert_ne!(ends[1].0.weekday(), Weekday::Sat);
+        assert_ne!(ends[1].0.weekday(), Weekday::Sun);
+    }
+
+    #[test]
+    fn assigns_the_same_contributors_for_the_same_seed() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let config = Config::default();
+        let model = config.model.build(&config);
+        let mut items: Vec<RoadmapItem> = ["one", "two", "three"]
+            .iter()
+            .map(|name| {
+                RoadmapItem::new(
+                    String::from(*name),
+                    1,
+                    1,
+                    today,
+                    today + Duration::days(365),
+                    vec![],
+                )
+            })
+            .collect();
+        items
+            .iter_mut()
+            .for_each(|item| item.update_urgency(model.as_ref(), today).unwrap());
+        let contributors: Vec<Contributor> = (1..=6)
+            .map(|i| Contributor::new(format!("IC{i}"), i % 5 + 1))
+            .collect();
+
+        let plans: Vec<Vec<Vec<Contributor>>> = (0..2)
+            .map(|_| {
+                schedule_roadmap_items(
+                    items.clone(),
+                    contributors.clone(),
+                    &Calendar::default(),
+                    &FixedClock(today),
+                    &config,
+                    &mut ChaCha8Rng::seed_from_u64(7),
+                )
+                .iter()
+                .map(|item| item.get_contributors())
+                .collect()
+            })
+            .collect();
+        assert_eq!(plans[0], plans[1]);
+    }
+
+    fn skilled_contributors() -> Vec<Contributor> {
+        let mut contributors = vec![
+            Contributor::new(String::from("IC1"), 1),
+            Contributor::new(String::from("IC2"), 3),
+            Contributor::new(String::from("IC3"), 5),
+        ];
+        contributors[1].skills = vec![Skill::new("ios", 4)];
+        return contributors;
+    }
+
+    fn item_requiring(skill: Skill) -> RoadmapItem {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let mut item = RoadmapItem::new(
+            String::from("app"),
+            1,
+            1,
+            today,
+            today + Duration::days(365),
+            vec![],
+        );
+        item.urgency = Some(0.4);
+        item.required_skills = vec![skill];
+        return item;
+    }
+
+    #[test]
+    fn prefers_contributors_with_the_required_skills() {
+        let config = Config::default();
+        let mut contributors = skilled_contributors();
+        let assigned = assign_contributors(
+            vec![item_requiring(Skill::new("ios", 3))],
+            &mut contributors,
+            &config,
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        let names: Vec<String> = assigned[0]
+            .get_contributors()
+            .iter()
+            .map(|contributor| contributor.name.clone())
+            .collect();
+        assert_eq!(names, vec!["IC2", "IC3"]);
+        assert!(assigned[0].get_skill_gaps().is_empty());
+
+        let mut contributors = skilled_contributors();
+        let assigned = assign_contributors(
+            vec![item_requiring(Skill::new("android", 1))],
+            &mut contributors,
+            &config,
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        assert_eq!(assigned[0].get_contributors().len(), 2);
+        assert_eq!(assigned[0].get_skill_gaps(), vec![Skill::new("android", 1)]);
+    }
+
+    #[test]
+    fn leaves_items_unstaffed_when_skills_are_required() {
+        let config = Config {
+            skill_matching: SkillMatching::Require,
+            ..Config::default()
+        };
+        let mut contributors = skilled_contributors();
+        let assigned = assign_contributors(
+            vec![item_requiring(Skill::new("ios", 3))],
+            &mut contributors,
+            &config,
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        assert_eq!(assigned[0].get_contributors().len(), 1);
+        assert_eq!(assigned[0].get_contributors()[0].name, "IC2");
+
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let scheduled = schedule_roadmap_items(
+            vec![item_requiring(Skill::new("android", 1))],
+            skilled_contributors(),
+            &Calendar::default(),
+            &FixedClock(today),
+            &config,
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        assert!(scheduled[0].get_contributors().is_empty());
+        assert_eq!(scheduled[0].scheduled_start, None);
+
+        // Covering all three skills would take more than the headcount of two
+        let mut contributors = skilled_contributors();
+        contributors[0].skills = vec![Skill::new("rust", 2)];
+        contributors[2].skills = vec![Skill::new("sql", 2)];
+        let mut item = item_requiring(Skill::new("ios", 3));
+        item.required_skills
+            .extend([Skill::new("rust", 1), Skill::new("sql", 1)]);
+        let assigned = assign_contributors(
+            vec![item],
+            &mut contributors,
+            &config,
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        assert!(assigned[0].get_contributors().is_empty());
+        assert_eq!(contributors.len(), 3);
+    }
+
+    #[test]
+    fn schedules_items_after_their_dependencies() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let mut items = vec![
+            item_requiring(Skill::new("ios", 1)),
+            item_requiring(Skill::new("ios", 1)),
+        ];
+        items[0].name = String::from("payments");
+        items[0].urgency = Some(0.9);
+        items[0].depends_on = vec![String::from("auth")];
+        items[1].name = String::from("auth");
+        items[1].urgency = Some(0.1);
+
+        let scheduled = schedule_roadmap_items(
+            items,
+            skilled_contributors(),
+            &Calendar::default(),
+            &FixedClock(today),
+            &Config::default(),
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        let auth = scheduled.iter().find(|item| item.name == "auth").unwrap();
+        let payments = scheduled
+            .iter()
+            .find(|item| item.name == "payments")
+            .unwrap();
+        assert_eq!(auth.scheduled_start, Some(today));
+        assert!(payments.scheduled_start.unwrap() > auth.scheduled_end.unwrap());
+    }
+
+    #[test]
+    fn waits_for_new_hires_and_holidays() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let mut new_hire = Contributor::new(String::from("IC1"), 3);
+        new_hire.available_from = Some(today + Duration::days(7));
+        let calendar = Calendar::new(vec![Absence::new(
+            None,
+            today + Duration::days(7),
+            today + Duration::days(7),
+            "Holiday",
+        )]);
+
+        let scheduled = schedule_roadmap_items(
+            vec![item_requiring(Skill::new("ios", 1))],
+            vec![new_hire],
+            &calendar,
+            &FixedClock(today),
+            &Config::default(),
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+        assert_eq!(
+            scheduled[0].scheduled_start,
+            Some(today + Duration::days(8))
+        );
+    }
+
+    #[test]
+    fn keeps_contributors_on_items_in_progress() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let start_date = NaiveDate::from_ymd_opt(2022, 10, 3).unwrap();
+        let target_date = today + Duration::days(365);
+        let item = |name: &str, complexity: usize, value: usize| {
+            RoadmapItem::new(
+                String::from(name),
+                complexity,
+                value,
+                start_date,
+                target_date,
+                vec![],
+            )
+        };
+        let mut ongoing = item("ongoing", 1, 1);
+        ongoing.status = Status::InProgress;
+        ongoing.assigned = vec![String::from("IC2")];
+        let mut shipped = item("shipped", 5, 5);
+        shipped.status = Status::Done;
+        let mut next = item("next", 5, 5);
+        next.depends_on = vec![String::from("shipped")];
+        let mut items = open_items(vec![ongoing, shipped, next]);
+        assert_eq!(items.len(), 2);
+        let config = Config::default();
+        let model = config.model.build(&config);
+        items
+            .iter_mut()
+            .for_each(|item| item.update_urgency(model.as_ref(), today).unwrap());
+        let contributors = vec![
+            Contributor::new(String::from("IC1"), 3),
+            Contributor::new(String::from("IC2"), 3),
+        ];
+
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let scheduled = schedule_roadmap_items(
+            items,
+            contributors,
+            &Calendar::default(),
+            &FixedClock(today),
+            &config,
+            &mut rng,
+        );
+
+        let ongoing = scheduled
+            .iter()
+            .find(|item| item.name == "ongoing")
+            .unwrap();
+        let next = scheduled.iter().find(|item| item.name == "next").unwrap();
+        assert_eq!(ongoing.get_contributors()[0].name, "IC2");
+        assert_eq!(ongoing.scheduled_start, Some(start_date));
+        assert_eq!(
+            ongoing.scheduled_end,
+            Some(NaiveDate::from_ymd_opt(2022, 10, 17).unwrap())
+        );
+        assert_eq!(next.get_contributors()[0].name, "IC1");
+        assert_eq!(next.scheduled_start, Some(today));
+    }
+}
@@ -0,0 +1,411 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Scale, Thresholds, Weights};
+use crate::error::KapacitetError;
+use crate::roadmap::{explain_project_urgency, Moscow, RoadmapItem};
+
+// WSJF components are all estimated on a 1-10 scale
+static MAX_WSJF_SCORE: f64 = 30.0;
+
+// Reach and effort are estimated on a 1-10 scale, impact between 0.25 and 3
+static MAX_RICE_SCORE: f64 = 30.0;
+
+pub trait ScoringModel {
+    /// Breaks down how the urgency of a roadmap item is calculated.
+    fn explain(
+        &self,
+        item: &RoadmapItem,
+        today: NaiveDate,
+    ) -> Result<ScoreBreakdown, KapacitetError>;
+
+    /// Scores a roadmap item between 0 (not urgent) and 1 (most urgent).
+    fn score(&self, item: &RoadmapItem, today: NaiveDate) -> Result<f64, KapacitetError> {
+        return Ok(self.explain(item, today)?.score);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreComponent {
+    pub name: &'static str,
+    pub raw: f64,
+    pub weight: f64,
+    pub contribution: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreBreakdown {
+    pub components: Vec<ScoreComponent>,
+    /// Sum of the contributions of all components
+    pub total: f64,
+    /// The total on a scale from 0 to 1, rounded to two decimals
+    pub score: f64,
+    /// Whether the score had to be clamped to fit between 0 and 1
+    pub clamped: bool,
+    /// The roadmap item waiting on this one that the score was inherited from
+    pub inherited_from: Option<String>,
+}
+
+impl ScoreComponent {
+    pub fn new(name: &'static str, raw: f64, weight: f64, contribution: f64) -> ScoreComponent {
+        return ScoreComponent {
+            name,
+            raw,
+            weight,
+            contribution,
+        };
+    }
+}
+
+impl ScoreBreakdown {
+    pub fn new(components: Vec<ScoreComponent>, normalize: impl Fn(f64) -> f64) -> ScoreBreakdown {
+        let total: f64 = components
+            .iter()
+            .map(|component| component.contribution)
+            .sum();
+        let normalized = normalize(total);
+        return ScoreBreakdown {
+            components,
+            total,
+            score: (normalized.clamp(0.0, 1.0) * 100.0).round() / 100.0,
+            clamped: !(0.0..=1.0).contains(&normalized),
+            inherited_from: None,
+        };
+    }
+
+    /// Names of the columns of `to_csv`.
+    pub fn csv_header(&self) -> Vec<String> {
+        let mut header = vec![String::from("total"), String::from("clamped")];
+        for component in &self.components {
+            header.push(format!("{0} raw", component.name));
+            header.push(format!("{0} weight", component.name));
+            header.push(format!("{0} contribution", component.name));
+        }
+        return header;
+    }
+
+    pub fn to_csv(&self) -> Vec<String> {
+        let mut record = vec![round(self.total).to_string(), self.clamped.to_string()];
+        for component in &self.components {
+            record.push(round(component.raw).to_string());
+            record.push(round(component.weight).to_string());
+            record.push(round(component.contribution).to_string());
+        }
+        return record;
+    }
+}
+
+impl Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "  {0:<20}{1:>12}{2:>12}{3:>14}",
+            "component", "raw", "weight", "contribution"
+        )?;
+        for component in &self.components {
+            writeln!(
+                f,
+                "  {0:<20}{1:>12}{2:>12}{3:>14}",
+                component.name,
+                round(component.raw),
+                round(component.weight),
+                round(component.contribution)
+            )?;
+        }
+        writeln!(f, "  {0:<44}{1:>14}", "total", round(self.total))?;
+        match &self.inherited_from {
+            Some(name) => write!(
+                f,
+                "  {0:<44}{1:>14}",
+                format!("urgency (inherited from {name})"),
+                self.score
+            ),
+            None if self.clamped => write!(
+                f,
+                "  {0:<44}{1:>14}",
+                "urgency (clamped to 0-1)", self.score
+            ),
+            None => write!(f, "  {0:<44}{1:>14}", "urgency", self.score),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Model {
+    #[default]
+    Weighted,
+    Wsjf,
+    Rice,
+    Moscow,
+}
+
+impl Model {
+    pub fn build(&self, config: &Config) -> Box<dyn ScoringModel> {
+        match self {
+            Model::Weighted => Box::new(WeightedModel {
+                weights: config.weights.clone(),
+                scale: config.scale.clone(),
+            }),
+            Model::Wsjf => Box::new(WsjfModel {}),
+            Model::Rice => Box::new(RiceModel {}),
+            Model::Moscow => Box::new(MoscowModel {
+                thresholds: config.thresholds.clone(),
+            }),
+        }
+    }
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Model, String> {
+        match s.to_lowercase().as_str() {
+            "weighted" => Ok(Model::Weighted),
+            "wsjf" => Ok(Model::Wsjf),
+            "rice" => Ok(Model::Rice),
+            "moscow" => Ok(Model::Moscow),
+            _ => Err(format!(
+                "Unknown scoring model {s}. Use one of weighted, wsjf, rice or moscow"
+            )),
+        }
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Model::Weighted => write!(f, "weighted"),
+            Model::Wsjf => write!(f, "wsjf"),
+            Model::Rice => write!(f, "rice"),
+            Model::Moscow => write!(f, "moscow"),
+        }
+    }
+}
+
+/// The default model, weighing target date, duration, complexity and value.
+pub struct WeightedModel {
+    pub weights: Weights,
+    pub scale: Scale,
+}
+
+impl ScoringModel for WeightedModel {
+    fn explain(
+        &self,
+        item: &RoadmapItem,
+        today: NaiveDate,
+    ) -> Result<ScoreBreakdown, KapacitetError> {
+        return Ok(explain_project_urgency(
+            item.estimated_complexity,
+            item.estimated_value,
+            item.start_date,
+            item.target_date,
+            today,
+            &self.weights,
+            &self.scale,
+        ));
+    }
+}
+
+/// Weighted shortest job first: cost of delay divided by job size.
+pub struct WsjfModel {}
+
+impl ScoringModel for WsjfModel {
+    fn explain(
+        &self,
+        item: &RoadmapItem,
+        _today: NaiveDate,
+    ) -> Result<ScoreBreakdown, KapacitetError> {
+        let business_value = require(item, "business_value", item.business_value)? as f64;
+        let time_criticality = require(item, "time_criticality", item.time_criticality)? as f64;
+        let risk_reduction = require(item, "risk_reduction", item.risk_reduction)? as f64;
+        let job_size = require(item, "job_size", item.job_size)? as f64;
+
+        // Each part of the cost of delay is divided by the job size
+        let weight = 1.0 / job_size;
+        let breakdown = ScoreBreakdown::new(
+            vec![
+                ScoreComponent::new(
+                    "business value",
+                    business_value,
+                    weight,
+                    business_value * weight,
+                ),
+                ScoreComponent::new(
+                    "time criticality",
+                    time_criticality,
+                    weight,
+                    time_criticality * weight,
+                ),
+                ScoreComponent::new(
+                    "risk reduction",
+                    risk_reduction,
+                    weight,
+                    risk_reduction * weight,
+                ),
+            ],
+            |wsjf| logarithmic(wsjf, MAX_WSJF_SCORE),
+        );
+        debug!("WSJF: {0}", breakdown.total);
+        return Ok(breakdown);
+    }
+}
+
+/// Reach times impact times confidence divided by effort.
+pub struct RiceModel {}
+
+impl ScoringModel for RiceModel {
+    fn explain(
+        &self,
+        item: &RoadmapItem,
+        _today: NaiveDate,
+    ) -> Result<ScoreBreakdown, KapacitetError> {
+        let reach = require(item, "reach", item.reach)? as f64;
+        let impact = require(item, "impact", item.impact)?;
+        let confidence = require(item, "confidence", item.confidence)? as f64 / 100.0;
+        let effort = require(item, "effort", item.effort)? as f64;
+
+        // The reach is weighed by the impact and confidence per unit of effort
+        let weight = impact * confidence / effort;
+        let breakdown = ScoreBreakdown::new(
+            vec![ScoreComponent::new("reach", reach, weight, reach * weight)],
+            |rice| logarithmic(rice, MAX_RICE_SCORE),
+        );
+        debug!("RICE: {0}", breakdown.total);
+        return Ok(breakdown);
+    }
+}
+
+/// Buckets items into must, should, could and won't have, lined up with the
+/// thresholds so that must haves get the most contributors.
+pub struct MoscowModel {
+    pub thresholds: Thresholds,
+}
+
+impl ScoringModel for MoscowModel {
+    fn explain(
+        &self,
+        item: &RoadmapItem,
+        _today: NaiveDate,
+    ) -> Result<ScoreBreakdown, KapacitetError> {
+        // The raw value is the rank of the bucket, from 4 for must to 1 for won't have
+        let (rank, bucket_score) = match require(item, "moscow", item.moscow)? {
+            Moscow::Must => (4.0, 1.0),
+            Moscow::Should => (3.0, self.thresholds.second),
+            Moscow::Could => (2.0, self.thresholds.first),
+            Moscow::Wont => (1.0, 0.0),
+        };
+        return Ok(ScoreBreakdown::new(
+            vec![ScoreComponent::new("moscow", rank, 1.0, bucket_score)],
+            |total| total,
+        ));
+    }
+}
+
+fn require<T>(
+    item: &RoadmapItem,
+    field: &'static str,
+    value: Option<T>,
+) -> Result<T, KapacitetError> {
+    return value.ok_or_else(|| KapacitetError::MissingScoringField(item.name.clone(), field));
+}
+
+// Ratio based scores grow quickly, so they are put on a logarithmic scale between 0 and 1
+fn logarithmic(score: f64, max_score: f64) -> f64 {
+    return score.ln_1p() / max_score.ln_1p();
+}
+
+fn round(value: f64) -> f64 {
+    return (value * 10000.0).round() / 10000.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::config::Config;
+    use crate::error::KapacitetError;
+    use crate::roadmap::{Moscow, RoadmapItem};
+    use crate::scoring::Model;
+
+    fn item() -> RoadmapItem {
+        return RoadmapItem::new(
+            String::from("MVP"),
+            4,
+            5,
+            NaiveDate::from_ymd_opt(2022, 6, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2022, 11, 1).unwrap(),
+            vec![],
+        );
+    }
+
+    #[test]
+    fn scores_wsjf_on_a_logarithmic_scale() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+        let model = Model::Wsjf.build(&Config::default());
+        let mut item = item();
+        item.business_value = Some(10);
+        item.time_criticality = Some(10);
+        item.risk_reduction = Some(10);
+        item.job_size = Some(1);
+        assert_eq!(model.score(&item, today).unwrap(), 1.0);
+
+        item.job_size = Some(10);
+        assert_eq!(model.score(&item, today).unwrap(), 0.4);
+    }
+
+    #[test]
+    fn scores_rice() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+        let model = Model::Rice.build(&Config::default());
+        let mut item = item();
+        item.reach = Some(5);
+        item.impact = Some(1.0);
+        item.confidence = Some(80);
+        item.effort = Some(2);
+        assert_eq!(model.score(&item, today).unwrap(), 0.32);
+    }
+
+    #[test]
+    fn buckets_moscow_by_thresholds() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+        let config = Config::default();
+        let model = Model::Moscow.build(&config);
+        let mut item = item();
+        item.moscow = Some(Moscow::Should);
+        assert_eq!(model.score(&item, today).unwrap(), config.thresholds.second);
+    }
+
+    #[test]
+    fn explains_the_weighted_score() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+        let model = Model::Weighted.build(&Config::default());
+        let breakdown = model.explain(&item(), today).unwrap();
+        let names: Vec<&str> = breakdown.components.iter().map(|c| c.name).collect();
+        assert_eq!(
+            names,
+            vec!["target date", "duration", "complexity", "value"]
+        );
+        assert_eq!(breakdown.components[0].raw, 17.0);
+        assert_eq!(breakdown.components[3].contribution, 0.4);
+        assert!(!breakdown.clamped);
+        assert_eq!(breakdown.score, 0.65);
+
+        let due_today = NaiveDate::from_ymd_opt(2022, 11, 1).unwrap();
+        assert!(model.explain(&item(), due_today).unwrap().clamped);
+    }
+
+    #[test]
+    fn requires_the_fields_of_the_model() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 15).unwrap();
+        let model = Model::Rice.build(&Config::default());
+        assert!(matches!(
+            model.score(&item(), today),
+            Err(KapacitetError::MissingScoringField(_, "reach"))
+        ));
+    }
+}
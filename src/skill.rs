@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use validator::ValidationError;
+
+use crate::contributor::Contributor;
+
+static DEFAULT_SKILL_LEVEL: usize = 1;
+static MIN_SKILL_LEVEL: usize = 1;
+static MAX_SKILL_LEVEL: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Skill {
+    pub name: String,
+    #[serde(default = "default_skill_level")]
+    pub level: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillMatching {
+    /// Prefer contributors with the required skills, but fall back to anyone available
+    #[default]
+    Prefer,
+    /// Only staff items with contributors who cover the required skills
+    Require,
+}
+
+impl Skill {
+    pub fn new(name: &str, level: usize) -> Skill {
+        return Skill {
+            name: name.trim().to_lowercase(),
+            level,
+        };
+    }
+}
+
+impl Display for Skill {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{0}:{1}", self.name, self.level)
+    }
+}
+
+impl FromStr for SkillMatching {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SkillMatching, String> {
+        match s.to_lowercase().as_str() {
+            "prefer" => Ok(SkillMatching::Prefer),
+            "require" => Ok(SkillMatching::Require),
+            _ => Err(format!(
+                "Unknown skill matching {s}. Use one of prefer or require"
+            )),
+        }
+    }
+}
+
+impl Display for SkillMatching {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SkillMatching::Prefer => write!(f, "prefer"),
+            SkillMatching::Require => write!(f, "require"),
+        }
+    }
+}
+
+/// Parses skills written as `name:level` separated by `;`, e.g. `rust:4;ios`.
+/// The level defaults to 1 when left out.
+pub fn parse_skills(text: &str) -> Result<Vec<Skill>, String> {
+    let mut skills: Vec<Skill> = Vec::new();
+    for skill in text.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        match skill.split_once(':') {
+            None => skills.push(Skill::new(skill, DEFAULT_SKILL_LEVEL)),
+            Some((name, level)) => match level.trim().parse::<usize>() {
+                Ok(level) => skills.push(Skill::new(name, level)),
+                Err(err) => {
+                    return Err(format!("Could not parse the level of skill {skill}: {err}"));
+                }
+            },
+        }
+    }
+    return Ok(skills);
+}
+
+/// Returns the required skills that none of the contributors have at the required level.
+pub fn missing_skills(required_skills: &[Skill], contributors: &[Contributor]) -> Vec<Skill> {
+    return required_skills
+        .iter()
+        .filter(|skill| {
+            !contributors
+                .iter()
+                .any(|contributor| contributor.has_skill(skill))
+        })
+        .cloned()
+        .collect();
+}
+
+pub fn validate_skills(skills: &[Skill]) -> Result<(), ValidationError> {
+    if skills
+        .iter()
+        .any(|skill| skill.level < MIN_SKILL_LEVEL || skill.level > MAX_SKILL_LEVEL)
+    {
+        return Err(ValidationError::new(
+            "Skill levels must be between 1 and 5.",
+        ));
+    }
+    if skills.iter().any(|skill| skill.name.is_empty()) {
+        return Err(ValidationError::new("Skill names cannot be empty."));
+    }
+    return Ok(());
+}
+
+fn default_skill_level() -> usize {
+    return DEFAULT_SKILL_LEVEL;
+}
+
+/// Deserializes skills from `rust:4;ios` text, a list of skills or a map of names to levels.
+pub fn deserialize_skills<'de, D>(deserializer: D) -> Result<Vec<Skill>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    return deserializer.deserialize_any(SkillsVisitor);
+}
+
+struct SkillsVisitor;
+
+// Skills in a list are either written like `rust:4` or as a name and a level
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SkillEntry {
+    Text(String),
+    Skill(Skill),
+}
+
+impl<'de> Visitor<'de> for SkillsVisitor {
+    type Value = Vec<Skill>;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "skills such as \"rust:4;ios\"")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Vec<Skill>, E> {
+        return parse_skills(value).map_err(E::custom);
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Vec<Skill>, E> {
+        return Ok(Vec::new());
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Vec<Skill>, E> {
+        return Ok(Vec::new());
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<Skill>, A::Error> {
+        let mut skills: Vec<Skill> = Vec::new();
+        while let Some(entry) = seq.next_element::<SkillEntry>()? {
+            match entry {
+                SkillEntry::Text(text) => {
+                    skills.extend(parse_skills(text.as_str()).map_err(A::Error::custom)?);
+                }
+                SkillEntry::Skill(skill) => {
+                    skills.push(Skill::new(skill.name.as_str(), skill.level));
+                }
+            }
+        }
+        return Ok(skills);
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Vec<Skill>, A::Error> {
+        let mut levels: BTreeMap<String, usize> = BTreeMap::new();
+        while let Some((name, level)) = map.next_entry::<String, usize>()? {
+            levels.insert(name, level);
+        }
+        return Ok(levels
+            .iter()
+            .map(|(name, level)| Skill::new(name, *level))
+            .collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contributor::Contributor;
+    use crate::skill::{missing_skills, parse_skills, Skill};
+
+    #[test]
+    fn parses_skills_with_optional_levels() {
+        assert_eq!(
+            parse_skills("Rust:4; ios ;").unwrap(),
+            vec![Skill::new("rust", 4), Skill::new("ios", 1)]
+        );
+        assert!(parse_skills("").unwrap().is_empty());
+        assert!(parse_skills("rust:expert").is_err());
+    }
+
+    #[test]
+    fn finds_skills_not_covered_by_contributors() {
+        let mut contributor = Contributor::new(String::from("IC1"), 3);
+        contributor.skills = vec![Skill::new("rust", 4), Skill::new("ios", 1)];
+        let required = vec![
+            Skill::new("rust", 3),
+            Skill::new("ios", 2),
+            Skill::new("sql", 1),
+        ];
+        assert_eq!(
+            missing_skills(&required, &[contributor]),
+            vec![Skill::new("ios", 2), Skill::new("sql", 1)]
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use crate::config::Config;
+use crate::contributor::Contributor;
+use crate::roadmap::RoadmapItem;
+use crate::schedule::{headcount, CAPACITY_EPSILON};
+use crate::skill::{missing_skills, SkillMatching};
+
+// Reward for staffing a slot of an item, scaled by its urgency. Every extra slot on the same
+// item is worth less, so that urgent items don't take everyone while others go without.
+static COVERAGE_REWARD: f64 = 1000.0;
+// Small enough that coverage always comes first
+static SENIORITY_REWARD: f64 = 10.0;
+static SKILL_REWARD: i64 = 100;
+// Items with no urgency should still be staffed when there is nothing better to do
+static MIN_URGENCY: f64 = 0.01;
+
+// Juniors (1-2), mid-level (3) and seniors (4-5)
+static SENIORITY_BANDS: usize = 3;
+
+// Capacity is assigned in steps of a quarter of a full-time position, and whatever is left
+// of a contributor's capacity as a final smaller step
+static ALLOCATION_STEP: f64 = 0.25;
+static STEPS_PER_FTE: usize = 4;
+
+/// Assigns the contributors by solving a min-cost flow problem instead of picking them one
+/// item at a time. The solution maximises the urgency-weighted coverage of the items, where
+/// every item gets at most its headcount in FTE and at most one FTE per seniority band.
+/// Contributors are allocated in quarter steps of their capacity, so they can be split
+/// across items, and capacity that isn't a multiple of a quarter is allocated as a final
+/// smaller step. Staying on the item they had in the previous plan is rewarded by the
+/// improvement threshold, so they only move when that covers the roadmap better by more
+/// than the threshold. Their remaining capacity is left in `contributors`.
+pub fn assign_contributors_optimally(
+    roadmap_items: Vec<RoadmapItem>,
+    contributors: &mut Vec<Contributor>,
+    config: &Config,
+) -> Vec<RoadmapItem> {
+    let mut excluded: Vec<bool> = vec![false; roadmap_items.len()];
+    let assignment = loop {
+        let assignment = solve(&roadmap_items, contributors, &excluded, config);
+        if config.skill_matching == SkillMatching::Prefer {
+            break assignment;
+        }
+
+        // Items that can't get all their required skills covered are left for later
+        let mut uncovered = false;
+        for (i, item) in roadmap_items.iter().enumerate() {
+            let team: Vec<Contributor> = assignment[i]
+                .iter()
+                .map(|(c, _)| contributors[*c].clone())
+                .collect();
+            if !team.is_empty() && !missing_skills(&item.required_skills, &team).is_empty() {
+                info!("Not enough skilled contributors available for item {item}");
+                excluded[i] = true;
+                uncovered = true;
+            }
+        }
+        if !uncovered {
+            break assignment;
+        }
+    };
+
+    let mut new_items: Vec<RoadmapItem> = Vec::new();
+    for (i, item) in roadmap_items.iter().enumerate() {
+        let mut new_item = item.clone();
+        // Leaving an item staffed in this wave gives up the reward for staying
+        for (c, _) in &assignment[i] {
+            let name = &contributors[*c].name;
+            if item.previous.contains(name) {
+                continue;
+            }
+            for (j, other) in roadmap_items.iter().enumerate() {
+                if j != i
+                    && !excluded[j]
+                    && other.previous.contains(name)
+                    && !assignment[j].iter().any(|(other_c, _)| other_c == c)
+                {
+                    new_item.move_reasons.insert(
+                        (name.clone(), other.name.clone()),
+                        format!(
+                            "Staffing {0} covers the roadmap better by more than the improvement threshold",
+                            item.name
+                        ),
+                    );
+                }
+            }
+        }
+        new_item.contributors = Some(
+            assignment[i]
+                .iter()
+                .map(|(c, allocation)| contributors[*c].allocate(*allocation))
+                .collect(),
+        );
+        info!("Finished assigning to roadmap item {new_item}");
+        new_items.push(new_item);
+    }
+
+    for (c, allocation) in assignment.into_iter().flatten() {
+        contributors[c].capacity -= allocation;
+    }
+    contributors.retain(|contributor| contributor.capacity > CAPACITY_EPSILON);
+    return new_items;
+}
+
+// Returns the indices and allocations of the contributors assigned to each item
+fn solve(
+    roadmap_items: &[RoadmapItem],
+    contributors: &[Contributor],
+    excluded: &[bool],
+    config: &Config,
+) -> Vec<Vec<(usize, f64)>> {
+    let source = 0;
+    let sink = 1;
+    let contributor_node = |c: usize| 2 + c;
+    let item_node = |i: usize| 2 + contributors.len() + i * (SENIORITY_BANDS + 1);
+    let band_node = |i: usize, band: usize| item_node(i) + 1 + band;
+    let mut graph =
+        Graph::new(2 + contributors.len() + roadmap_items.len() * (SENIORITY_BANDS + 1));
+
+    let steps: Vec<i64> = contributors
+        .iter()
+        .map(|contributor| {
+            (contributor.capacity / ALLOCATION_STEP - CAPACITY_EPSILON).ceil() as i64
+        })
+        .collect();
+    for (c, capacity) in steps.iter().enumerate() {
+        graph.add_edge(source, contributor_node(c), *capacity, 0);
+    }
+    let continuity =
+        (COVERAGE_REWARD * config.stability.improvement_threshold * ALLOCATION_STEP).round() as i64;
+    let mut assignment_edges: Vec<(usize, usize, usize)> = Vec::new();
+    for (i, item) in roadmap_items.iter().enumerate() {
+        if excluded[i] {
+            continue;
+        }
+        let urgency = f64::max(item.get_urgency(), MIN_URGENCY);
+        for slot in 0..headcount(item, config) * STEPS_PER_FTE {
+            let reward =
+                COVERAGE_REWARD * urgency * ALLOCATION_STEP / (slot / STEPS_PER_FTE + 1) as f64;
+            graph.add_edge(item_node(i), sink, 1, -(reward.round() as i64));
+        }
+        for band in 0..SENIORITY_BANDS {
+            graph.add_edge(band_node(i, band), item_node(i), STEPS_PER_FTE as i64, 0);
+        }
+        for (c, contributor) in contributors.iter().enumerate() {
+            let skills = item
+                .required_skills
+                .iter()
+                .filter(|skill| contributor.has_skill(skill))
+                .count() as i64;
+            if config.skill_matching == SkillMatching::Require
+                && !item.required_skills.is_empty()
+                && skills == 0
+            {
+                continue;
+            }
+            let mut reward =
+                (SENIORITY_REWARD * urgency * ALLOCATION_STEP * contributor.seniority as f64)
+                    .round() as i64
+                    + SKILL_REWARD * skills;
+            if item.previous.contains(&contributor.name) {
+                reward += continuity;
+            }
+            let edge = graph.add_edge(
+                contributor_node(c),
+                band_node(i, seniority_band(contributor.seniority)),
+                steps[c],
+                -reward,
+            );
+            assignment_edges.push((c, i, edge));
+        }
+    }
+
+    graph.min_cost_flow(source, sink);
+
+    let mut assignment: Vec<Vec<(usize, f64)>> = vec![Vec::new(); roadmap_items.len()];
+    let mut allocated: Vec<f64> = vec![0.0; contributors.len()];
+    for (c, i, edge) in assignment_edges {
+        let flow = graph.flow(contributor_node(c), edge);
+        if flow > 0 {
+            // The final step only takes what is left, rounded to keep out float noise
+            let left = ((contributors[c].capacity - allocated[c]) * 1e6).round() / 1e6;
+            let allocation = f64::min(flow as f64 * ALLOCATION_STEP, left);
+            allocated[c] += allocation;
+            assignment[i].push((c, allocation));
+        }
+    }
+    return assignment;
+}
+
+/// Index of the seniority band of a seniority level: 0 for juniors, 1 for mid-level and 2
+/// for seniors.
+pub(crate) fn seniority_band(seniority: usize) -> usize {
+    match seniority {
+        0..=2 => 0,
+        3 => 1,
+        _ => 2,
+    }
+}
+
+struct Edge {
+    to: usize,
+    initial_capacity: i64,
+    capacity: i64,
+    cost: i64,
+    reverse: usize,
+}
+
+struct Graph {
+    edges: Vec<Vec<Edge>>,
+}
+
+impl Graph {
+    fn new(nodes: usize) -> Graph {
+        return Graph {
+            edges: (0..nodes).map(|_| Vec::new()).collect(),
+        };
+    }
+
+    // Returns the index of the edge in the adjacency list of `from`
+    fn add_edge(&mut self, from: usize, to: usize, capacity: i64, cost: i64) -> usize {
+        let index = self.edges[from].len();
+        let reverse = self.edges[to].len();
+        self.edges[from].push(Edge {
+            to,
+            initial_capacity: capacity,
+            capacity,
+            cost,
+            reverse,
+        });
+        self.edges[to].push(Edge {
+            to: from,
+            initial_capacity: 0,
+            capacity: 0,
+            cost: -cost,
+            reverse: index,
+        });
+        return index;
+    }
+
+    fn flow(&self, from: usize, edge: usize) -> i64 {
+        let edge = &self.edges[from][edge];
+        return edge.initial_capacity - edge.capacity;
+    }
+
+    // Successive shortest paths with Bellman-Ford, as the rewards are negative costs. Stops
+    // as soon as sending more flow would no longer lower the total cost.
+    fn min_cost_flow(&mut self, source: usize, sink: usize) {
+        loop {
+            let mut distance: Vec<Option<i64>> = vec![None; self.edges.len()];
+            let mut previous: Vec<Option<(usize, usize)>> = vec![None; self.edges.len()];
+            distance[source] = Some(0);
+            let mut updated = true;
+            while updated {
+                updated = false;
+                for node in 0..self.edges.len() {
+                    let Some(node_distance) = distance[node] else {
+                        continue;
+                    };
+                    for (index, edge) in self.edges[node].iter().enumerate() {
+                        if edge.capacity <= 0 {
+                            continue;
+                        }
+                        let candidate = node_distance + edge.cost;
+                        if distance[edge.to].is_none_or(|d| candidate < d) {
+                            distance[edge.to] = Some(candidate);
+                            previous[edge.to] = Some((node, index));
+                            updated = true;
+                        }
+                    }
+                }
+            }
+
+            match distance[sink] {
+                Some(cost) if cost < 0 => {}
+                _ => {
+                    return;
+                }
+            }
+
+            let mut path: Vec<(usize, usize)> = Vec::new();
+            let mut node = sink;
+            while let Some((from, index)) = previous[node] {
+                path.push((from, index));
+                node = from;
+            }
+            let flow = path
+                .iter()
+                .map(|(from, index)| self.edges[*from][*index].capacity)
+                .min()
+                .unwrap_or(0);
+            for (from, index) in path {
+                let (to, reverse) = {
+                    let edge = &mut self.edges[from][index];
+                    edge.capacity -= flow;
+                    (edge.to, edge.reverse)
+                };
+                self.edges[to][reverse].capacity += flow;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::config::Config;
+    use crate::contributor::Contributor;
+    use crate::roadmap::RoadmapItem;
+    use crate::skill::{Skill, SkillMatching};
+    use crate::solver::assign_contributors_optimally;
+
+    fn item(name: &str, urgency: f64) -> RoadmapItem {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let mut item = RoadmapItem::new(
+            String::from(name),
+            1,
+            1,
+            today,
+            today + Duration::days(365),
+            vec![],
+        );
+        item.urgency = Some(urgency);
+        return item;
+    }
+
+    fn names(item: &RoadmapItem) -> Vec<String> {
+        return item
+            .get_contributors()
+            .iter()
+            .map(|contributor| contributor.name.clone())
+            .collect();
+    }
+
+    #[test]
+    fn spreads_contributors_by_urgency_and_seniority() {
+        let mut contributors = vec![
+            Contributor::new(String::from("IC1"), 1),
+            Contributor::new(String::from("IC4"), 4),
+            Contributor::new(String::from("IC5"), 5),
+        ];
+        // The greedy heuristic gives all three to the first item and leaves the second empty
+        let assigned = assign_contributors_optimally(
+            vec![item("urgent", 0.7), item("later", 0.35)],
+            &mut contributors,
+            &Config::default(),
+        );
+        assert_eq!(names(&assigned[0]), vec!["IC1", "IC5"]);
+        assert_eq!(names(&assigned[1]), vec!["IC4"]);
+        assert!(contributors.is_empty());
+    }
+
+    #[test]
+    fn keeps_contributors_without_the_required_skills() {
+        let mut contributors = vec![
+            Contributor::new(String::from("IC1"), 1),
+            Contributor::new(String::from("IC3"), 3),
+        ];
+        contributors[1].skills = vec![Skill::new("ios", 3)];
+        let mut app = item("app", 0.7);
+        app.required_skills = vec![Skill::new("ios", 2), Skill::new("rust", 1)];
+        let mut web = item("web", 0.2);
+        web.required_skills = vec![Skill::new("ios", 1)];
+        let config = Config {
+            skill_matching: SkillMatching::Require,
+            ..Config::default()
+        };
+
+        let assigned = assign_contributors_optimally(vec![app, web], &mut contributors, &config);
+        assert!(assigned[0].get_contributors().is_empty());
+        assert_eq!(names(&assigned[1]), vec!["IC3"]);
+        assert_eq!(contributors.len(), 1);
+        assert_eq!(contributors[0].name, "IC1");
+    }
+
+    #[test]
+    fn fills_the_capacity_of_contributors() {
+        let mut contributors = vec![
+            Contributor::new(String::from("lead"), 2),
+            Contributor::new(String::from("IC1"), 1),
+        ];
+        contributors[0].capacity = 0.5;
+        // Each item needs a single FTE and takes at most one FTE of juniors
+        let assigned = assign_contributors_optimally(
+            vec![item("urgent", 0.25), item("later", 0.2)],
+            &mut contributors,
+            &Config::default(),
+        );
+        let allocated = |item: &RoadmapItem| -> f64 {
+            item.get_contributors()
+                .iter()
+                .map(|contributor| contributor.get_allocation())
+                .sum()
+        };
+        assert_eq!(allocated(&assigned[0]), 1.0);
+        assert_eq!(allocated(&assigned[1]), 0.5);
+        let lead: f64 = assigned
+            .iter()
+            .flat_map(|item| item.get_contributors())
+            .filter(|contributor| contributor.name == "lead")
+            .map(|contributor| contributor.get_allocation())
+            .sum();
+        assert_eq!(lead, 0.5);
+        assert!(contributors.is_empty());
+    }
+
+    #[test]
+    fn allocates_capacity_below_a_quarter_step() {
+        let mut contributors = vec![
+            Contributor::new(String::from("IC1"), 1),
+            Contributor::new(String::from("IC3"), 3),
+        ];
+        contributors[0].capacity = 0.1;
+        contributors[1].capacity = 0.6;
+        let assigned = assign_contributors_optimally(
+            vec![item("app", 0.7)],
+            &mut contributors,
+            &Config::default(),
+        );
+        let allocations: Vec<(String, f64)> = assigned[0]
+            .get_contributors()
+            .iter()
+            .map(|contributor| (contributor.name.clone(), contributor.get_allocation()))
+            .collect();
+        assert_eq!(
+            allocations,
+            vec![(String::from("IC1"), 0.1), (String::from("IC3"), 0.6)]
+        );
+        assert!(contributors.is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+use serde::Serialize;
+
+use crate::contributor::Contributor;
+use crate::report::ItemReport;
+use crate::roadmap::RoadmapItem;
+
+/// A contributor who left a roadmap item they had in the previous plan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reassignment {
+    pub contributor: String,
+    /// Item the contributor had in the previous plan
+    pub from: String,
+    /// Items the contributor joined instead
+    pub to: Vec<String>,
+    pub reason: String,
+}
+
+/// Records who was on each roadmap item in the previous plan, so that they can stay there.
+pub fn carry_over(roadmap_items: &mut [RoadmapItem], previous_plan: &[ItemReport]) {
+    for item in roadmap_items.iter_mut() {
+        if let Some(previous) = previous_plan.iter().find(|p| p.name == item.name) {
+            item.previous = previous
+                .contributors
+                .iter()
+                .map(|contributor| contributor.name.clone())
+                .collect();
+        }
+    }
+}
+
+/// Lists every contributor who is no longer on an item they had in the previous plan, with
+/// the reason why.
+pub fn reassignments(
+    previous_plan: &[ItemReport],
+    roadmap_items: &[RoadmapItem],
+    contributors: &[Contributor],
+) -> Vec<Reassignment> {
+    let on_item = |item: &RoadmapItem, name: &str| {
+        return item
+            .get_contributors()
+            .iter()
+            .any(|contributor| contributor.name == name);
+    };
+    let was_on_item = |item: &RoadmapItem, name: &str| {
+        return previous_plan.iter().any(|previous| {
+            previous.name == item.name
+                && previous
+                    .contributors
+                    .iter()
+                    .any(|contributor| contributor.name == name)
+        });
+    };
+
+    let mut reassignments = Vec::new();
+    for previous in previous_plan {
+        let item = roadmap_items.iter().find(|item| item.name == previous.name);
+        for name in previous.contributors.iter().map(|c| c.name.as_str()) {
+            if item.is_some_and(|item| on_item(item, name)) {
+                continue;
+            }
+            let mut to: Vec<&RoadmapItem> = roadmap_items
+                .iter()
+                .filter(|other| on_item(other, name) && !was_on_item(other, name))
+                .collect();
+            to.sort();
+
+            // Moves are explained by the assigner that decided them
+            let key = (String::from(name), previous.name.clone());
+            let decided = to.iter().find_map(|other| other.move_reasons.get(&key));
+            let reason = match (item, decided) {
+                _ if !contributors.iter().any(|c| c.name == name) => {
+                    format!("{name} is no longer on the team")
+                }
+                (None, _) => format!("{0} is no longer planned", previous.name),
+                (Some(_), Some(reason)) => reason.clone(),
+                (Some(item), None) if item.get_contributors().is_empty() => {
+                    format!("{0} is not staffed in this plan", item.name)
+                }
+                (Some(item), None) => format!("{0} is staffed without them", item.name),
+            };
+            reassignments.push(Reassignment {
+                contributor: String::from(name),
+                from: previous.name.clone(),
+                to: to.iter().map(|other| other.name.clone()).collect(),
+                reason,
+            });
+        }
+    }
+    return reassignments;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::config::{Config, Stability};
+    use crate::contributor::Contributor;
+    use crate::report::ItemReport;
+    use crate::roadmap::RoadmapItem;
+    use crate::schedule::{assign_contributors, Strategy};
+    use crate::solver::assign_contributors_optimally;
+    use crate::stability::{carry_over, reassignments};
+
+    #[test]
+    fn keeps_assignments_unless_moving_gains_more_than_the_threshold() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let item = |name: &str, urgency: f64| {
+            let mut item = RoadmapItem::new(
+                String::from(name),
+                1,
+                1,
+                today,
+                today + Duration::days(365),
+                vec![],
+            );
+            item.urgency = Some(urgency);
+            item
+        };
+        let contributors = vec![Contributor::new(String::from("IC1"), 3)];
+        let mut previous = item("web", 0.4);
+        previous.contributors = Some(contributors.clone());
+        let previous_plan = vec![ItemReport::from(&previous)];
+        let assign = |strategy: Strategy, threshold: f64| {
+            let mut roadmap_items = vec![item("app", 0.5), item("web", 0.4)];
+            carry_over(&mut roadmap_items, &previous_plan);
+            let config = Config {
+                stability: Stability {
+                    improvement_threshold: threshold,
+                },
+                ..Config::default()
+            };
+            let mut contributors = contributors.clone();
+            match strategy {
+                Strategy::Greedy => {
+                    let mut rng = ChaCha8Rng::seed_from_u64(0);
+                    return assign_contributors(
+                        roadmap_items,
+                        &mut contributors,
+                        &config,
+                        &mut rng,
+                    );
+                }
+                Strategy::Optimal => {
+                    return assign_contributors_optimally(
+                        roadmap_items,
+                        &mut contributors,
+                        &config,
+                    );
+                }
+            }
+        };
+
+        for (strategy, reason) in [
+            (
+                Strategy::Optimal,
+                "Staffing app covers the roadmap better by more than the improvement threshold",
+            ),
+            (
+                Strategy::Greedy,
+                "app is more urgent (0.50 over 0.40) by more than the improvement threshold",
+            ),
+        ] {
+            // A gain of 0.1 in urgency doesn't justify moving them
+            let stable = assign(strategy, 0.5);
+            assert!(stable[0].get_contributors().is_empty());
+            assert_eq!(stable[1].get_contributors()[0].name, "IC1");
+            assert!(reassignments(&previous_plan, &stable, &contributors).is_empty());
+
+            let moved = assign(strategy, 0.0);
+            assert_eq!(moved[0].get_contributors()[0].name, "IC1");
+            let moves = reassignments(&previous_plan, &moved, &contributors);
+            assert_eq!(moves.len(), 1);
+            assert_eq!(moves[0].from, "web");
+            assert_eq!(moves[0].to, vec!["app"]);
+            assert_eq!(moves[0].reason, reason);
+        }
+    }
+}
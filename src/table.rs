@@ -0,0 +1,330 @@
+use std::io::Write;
+
+use chrono::NaiveDate;
+
+use crate::config::UrgencyBand;
+use crate::error::KapacitetError;
+use crate::report::{breakdown_columns, ContributorReport, ItemReport, PlanReport};
+use crate::solver::seniority_band;
+
+static TABLE_HEADER: [&str; 8] = [
+    "#",
+    "item",
+    "urgency",
+    "contributors",
+    "seniority",
+    "scheduled start",
+    "scheduled end",
+    "target date",
+];
+static URGENCY_COLUMN: usize = 2;
+static BAR_WIDTH: usize = 10;
+// Labels of the seniority bands, from juniors to seniors
+static SENIORITY_LABELS: [&str; 3] = ["junior", "mid", "senior"];
+
+static RESET: &str = "\x1b[0m";
+
+impl PlanReport {
+    /// Writes the items as a table with aligned columns for reading in a terminal, followed
+    /// by a summary of the staffing. With `colour`, urgency bars are coloured by band, and
+    /// with `explain` the components of the urgency scores are added as columns.
+    pub fn write_table<W: Write>(
+        &self,
+        mut writer: W,
+        colour: bool,
+        explain: bool,
+    ) -> Result<(), KapacitetError> {
+        let mut rows = vec![self.table_header(explain)];
+        rows.extend(self.table_rows(explain));
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|column| {
+                return rows
+                    .iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or_default();
+            })
+            .collect();
+
+        let mut table = String::new();
+        for (i, row) in rows.iter().enumerate() {
+            let mut line = String::new();
+            for (column, cell) in row.iter().enumerate() {
+                let padding = widths[column] - cell.chars().count() + 2;
+                if colour && column == URGENCY_COLUMN && i > 0 {
+                    let band = self.metadata.thresholds.band(self.items[i - 1].urgency);
+                    line.push_str(&format!("{0}{cell}{RESET}", ansi_colour(band)));
+                } else {
+                    line.push_str(cell);
+                }
+                line.push_str(&" ".repeat(padding));
+            }
+            table.push_str(line.trim_end());
+            table.push('\n');
+            if i == 0 {
+                let width: usize = widths.iter().map(|width| width + 2).sum();
+                table.push_str(&"─".repeat(width - 2));
+                table.push('\n');
+            }
+        }
+        table.push('\n');
+        for line in self.summary() {
+            table.push_str(&line);
+            table.push('\n');
+        }
+        return writer
+            .write_all(table.as_bytes())
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    /// Writes the items as a GitHub-flavored Markdown table followed by a summary of the
+    /// staffing, to paste into pull requests and docs. With `explain` the components of the
+    /// urgency scores are added as columns.
+    pub fn write_markdown<W: Write>(
+        &self,
+        mut writer: W,
+        explain: bool,
+    ) -> Result<(), KapacitetError> {
+        let header = self.table_header(explain);
+        let mut markdown = format!(
+            "| {0} |\n|{1}\n",
+            header.join(" | "),
+            "---|".repeat(header.len())
+        );
+        for row in self.table_rows(explain) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+                .collect();
+            markdown.push_str(&format!("| {0} |\n", cells.join(" | ")));
+        }
+        markdown.push('\n');
+        // Ending lines with two spaces keeps the summary on separate lines
+        markdown.push_str(&self.summary().join("  \n"));
+        markdown.push('\n');
+        return writer
+            .write_all(markdown.as_bytes())
+            .map_err(|err| KapacitetError::UnwritableOutput(err.into()));
+    }
+
+    fn table_header(&self, explain: bool) -> Vec<String> {
+        let mut header = TABLE_HEADER.map(String::from).to_vec();
+        header.extend(self.breakdown_header(explain));
+        return header;
+    }
+
+    // Items without a breakdown get empty components, so that every row has all columns
+    fn table_rows(&self, explain: bool) -> Vec<Vec<String>> {
+        let columns = self.table_header(explain).len();
+        return self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let mut row = table_row((i, item));
+                row.extend(breakdown_columns(item, explain));
+                row.resize(columns, String::from("-"));
+                return row;
+            })
+            .collect();
+    }
+
+    // How many items are staffed with how much capacity, and who is left out
+    fn summary(&self) -> Vec<String> {
+        let staffed = self
+            .items
+            .iter()
+            .filter(|item| !item.contributors.is_empty())
+            .count();
+        let fte: f64 = self
+            .items
+            .iter()
+            .flat_map(|item| item.contributors.iter())
+            .map(|contributor| contributor.allocation)
+            .sum();
+        let unstaffed: Vec<&str> = self
+            .items
+            .iter()
+            .filter(|item| item.contributors.is_empty())
+            .map(|item| item.name.as_str())
+            .collect();
+        return vec![
+            format!(
+                "Staffed: {staffed} of {0} items with {1} FTE",
+                self.items.len(),
+                (fte * 100.0).round() / 100.0
+            ),
+            format!("Unstaffed items: {0}", or_none(&unstaffed.join(", "))),
+            format!(
+                "Idle contributors: {0}",
+                or_none(&allocations(&self.unassigned_contributors))
+            ),
+        ];
+    }
+}
+
+fn table_row((i, item): (usize, &ItemReport)) -> Vec<String> {
+    let date = |date: Option<NaiveDate>| {
+        return date
+            .map(|date| date.to_string())
+            .unwrap_or_else(|| String::from("-"));
+    };
+    return vec![
+        (i + 1).to_string(),
+        item.name.clone(),
+        format!("{0} {1:.2}", urgency_bar(item.urgency), item.urgency),
+        or_none(&allocations(&item.contributors)),
+        seniority_mix(&item.contributors),
+        date(item.scheduled_start),
+        date(item.scheduled_end),
+        item.target_date.to_string(),
+    ];
+}
+
+fn urgency_bar(urgency: f64) -> String {
+    let filled = ((urgency.clamp(0.0, 1.0) * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    return format!("{0}{1}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled));
+}
+
+/// Counts the contributors in each seniority band, like `1 senior, 2 junior`.
+fn seniority_mix(contributors: &[ContributorReport]) -> String {
+    let mut counts = [0; SENIORITY_LABELS.len()];
+    for contributor in contributors {
+        counts[seniority_band(contributor.seniority)] += 1;
+    }
+    let mix: Vec<String> = counts
+        .iter()
+        .zip(SENIORITY_LABELS)
+        .rev()
+        .filter(|(count, _)| **count > 0)
+        .map(|(count, label)| format!("{count} {label}"))
+        .collect();
+    return or_none(&mix.join(", "));
+}
+
+fn allocations(contributors: &[ContributorReport]) -> String {
+    return contributors
+        .iter()
+        .map(|contributor| {
+            format!(
+                "{0} ({1}%)",
+                contributor.name,
+                (contributor.allocation * 100.0).round()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+fn or_none(text: &str) -> String {
+    if text.is_empty() {
+        return String::from("-");
+    }
+    return String::from(text);
+}
+
+fn ansi_colour(band: UrgencyBand) -> &'static str {
+    match band {
+        UrgencyBand::High => "\x1b[31m",
+        UrgencyBand::Medium => "\x1b[33m",
+        UrgencyBand::Low => "\x1b[34m",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::config::Config;
+    use crate::contributor::Contributor;
+    use crate::report::PlanReport;
+    use crate::roadmap::RoadmapItem;
+    use crate::scoring::{ScoreBreakdown, ScoreComponent};
+
+    #[test]
+    fn aligns_columns_and_summarises_staffing() {
+        let today = NaiveDate::from_ymd_opt(2022, 10, 17).unwrap();
+        let ada = Contributor::new(String::from("Ada"), 5);
+        let bob = Contributor::new(String::from("Bob"), 1);
+        let cyd = Contributor::new(String::from("Cyd"), 2);
+        let mut app = RoadmapItem::new(
+            String::from("App"),
+            1,
+            1,
+            today,
+            today + Duration::days(30),
+            vec![ada.allocate(0.5), bob.allocate(1.0)],
+        );
+        app.urgency = Some(0.7);
+        app.set_schedule(today, today + Duration::days(9));
+        let mut web = RoadmapItem::new(
+            String::from("Web | v2"),
+            1,
+            1,
+            today,
+            today + Duration::days(60),
+            vec![],
+        );
+        web.urgency = Some(0.1);
+        let mut report = PlanReport::new(&[app, web], &[ada, bob, cyd], today, &Config::default());
+
+        let mut output: Vec<u8> = Vec::new();
+        report.write_table(&mut output, false, false).unwrap();
+        let table = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[0],
+            "#  item      urgency          contributors           seniority           scheduled start  scheduled end  target date"
+        );
+        assert_eq!(
+            lines[2],
+            "1  App       ███████░░░ 0.70  Ada (50%), Bob (100%)  1 senior, 1 junior  2022-10-17       2022-10-26     2022-11-16"
+        );
+        assert_eq!(
+            lines[3],
+            "2  Web | v2  █░░░░░░░░░ 0.10  -                      -                   -                -              2022-12-16"
+        );
+        assert_eq!(
+            &lines[5..],
+            [
+                "Staffed: 1 of 2 items with 1.5 FTE",
+                "Unstaffed items: Web | v2",
+                "Idle contributors: Cyd (100%)"
+            ]
+        );
+
+        let mut output: Vec<u8> = Vec::new();
+        report.write_markdown(&mut output, false).unwrap();
+        let markdown = String::from_utf8(output).unwrap();
+        assert!(markdown.starts_with("| # | item | urgency |"));
+        assert!(markdown.contains("\n|---|---|---|---|---|---|---|---|\n"));
+        assert!(markdown.contains("| 2 | Web \\| v2 | █░░░░░░░░░ 0.10 | - |"));
+        assert!(markdown.ends_with("Idle contributors: Cyd (100%)\n"));
+
+        report.items[0].score_breakdown = Some(ScoreBreakdown {
+            components: vec![ScoreComponent {
+                name: "value",
+                raw: 0.7,
+                weight: 1.0,
+                contribution: 0.7,
+            }],
+            total: 0.7,
+            score: 0.7,
+            clamped: false,
+            inherited_from: None,
+        });
+        let mut output: Vec<u8> = Vec::new();
+        report.write_table(&mut output, false, true).unwrap();
+        let table = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0]
+            .ends_with("target date  total  clamped  value raw  value weight  value contribution"));
+        assert!(lines[2].ends_with("2022-11-16   0.7    false    0.7        1             0.7"));
+        assert!(lines[3].ends_with("2022-12-16   -      -        -          -             -"));
+
+        let mut output: Vec<u8> = Vec::new();
+        report.write_markdown(&mut output, true).unwrap();
+        let markdown = String::from_utf8(output).unwrap();
+        assert!(markdown.contains("| 2022-11-16 | 0.7 | false | 0.7 | 1 | 0.7 |\n"));
+    }
+}
@@ -0,0 +1,872 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidateArgs, ValidationErrors};
+
+use crate::calendar::{Absence, Calendar};
+use crate::config::Config;
+use crate::contributor::Contributor;
+use crate::dependency::topological_order;
+use crate::error::KapacitetError;
+use crate::input::{read_calendar_file, values_from_document, values_from_toml, InputFormat};
+use crate::roadmap::{RoadmapItem, Status};
+use crate::schedule::{estimate_effort, MAX_HEADCOUNT};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Fails validation and stops planning
+    #[default]
+    Error,
+    /// Is reported, but does not fail validation
+    Warning,
+    /// Is not checked
+    Off,
+}
+
+/// A rule that a record of an input file breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub file_path: String,
+    /// Line of the record, only known in CSV files
+    pub line: Option<u64>,
+    /// Name of the record, or its number when it has no name
+    pub record: Option<String>,
+    /// Column or field that breaks the rule
+    pub column: Option<String>,
+    pub rule: String,
+    pub severity: Severity,
+}
+
+/// Outcome of checking every record of an input file instead of stopping at the first
+/// problem.
+#[derive(Debug)]
+pub struct Validation<T> {
+    /// Number of records without problems
+    pub valid: usize,
+    pub problems: Vec<Problem>,
+    /// Records that could be read, including the ones that break rules, so that the file
+    /// can be checked as a whole
+    pub records: Vec<Record<T>>,
+}
+
+/// A record read from an input file together with where it was found.
+#[derive(Debug, Clone)]
+pub struct Record<T> {
+    pub value: T,
+    /// Line of the record, only known in CSV files
+    pub line: Option<u64>,
+}
+
+impl Problem {
+    fn new(file_path: &str, line: Option<u64>, rule: String) -> Problem {
+        return Problem {
+            file_path: String::from(file_path),
+            line,
+            record: None,
+            column: None,
+            rule,
+            severity: Severity::Error,
+        };
+    }
+}
+
+impl<T> Validation<T> {
+    /// Whether there are no problems with the severity of an error.
+    pub fn is_valid(&self) -> bool {
+        return errors(&self.problems) == 0;
+    }
+
+    pub fn extend(&mut self, other: Validation<T>) {
+        self.valid += other.valid;
+        self.problems.extend(other.problems);
+        self.records.extend(other.records);
+    }
+}
+
+impl<T> Default for Validation<T> {
+    fn default() -> Validation<T> {
+        return Validation {
+            valid: 0,
+            problems: Vec::new(),
+            records: Vec::new(),
+        };
+    }
+}
+
+impl<T> Record<T> {
+    pub fn new(value: T, line: Option<u64>) -> Record<T> {
+        return Record { value, line };
+    }
+}
+
+pub fn check_contributors_file(file_path: &str, format: InputFormat) -> Validation<Contributor> {
+    let mut problems = Vec::new();
+    let records = read_records(file_path, format, "contributors", &mut problems);
+    return with_problems(problems, check_contributors(file_path, records));
+}
+
+pub fn check_roadmap_file(
+    file_path: &str,
+    format: InputFormat,
+    today: NaiveDate,
+) -> Validation<RoadmapItem> {
+    let mut problems = Vec::new();
+    let records = read_records(file_path, format, "roadmap", &mut problems);
+    return with_problems(problems, check_roadmap(file_path, records, today));
+}
+
+/// Checks holidays and time off in CSV. iCalendar files are only checked as a whole.
+pub fn check_calendar_file(file_path: &str) -> Validation<Absence> {
+    let is_ics = Path::new(file_path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"));
+    if is_ics {
+        match read_calendar_file(file_path) {
+            Ok(calendar) => {
+                return Validation {
+                    valid: calendar.absences.len(),
+                    problems: Vec::new(),
+                    records: calendar
+                        .absences
+                        .into_iter()
+                        .map(|absence| Record::new(absence, None))
+                        .collect(),
+                };
+            }
+            Err(err) => {
+                return Validation {
+                    problems: vec![Problem::new(file_path, None, err.to_string())],
+                    ..Validation::default()
+                };
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    let records = read_records::<Absence>(file_path, InputFormat::Csv, "calendar", &mut problems);
+    let mut valid = 0;
+    for record in &records {
+        match record.value.validate() {
+            Ok(()) => valid += 1,
+            Err(errors) => problems.extend(rule_problems(
+                file_path,
+                record,
+                record.value.description.as_str(),
+                errors,
+            )),
+        }
+    }
+    return Validation {
+        valid,
+        problems,
+        records,
+    };
+}
+
+pub(crate) fn check_contributors(
+    file_path: &str,
+    records: Vec<Record<Contributor>>,
+) -> Validation<Contributor> {
+    let mut validation = Validation::default();
+    for record in &records {
+        match record.value.validate() {
+            Ok(()) => validation.valid += 1,
+            Err(errors) => validation.problems.extend(rule_problems(
+                file_path,
+                record,
+                record.value.name.as_str(),
+                errors,
+            )),
+        }
+    }
+    validation.records = records;
+    return validation;
+}
+
+pub(crate) fn check_roadmap(
+    file_path: &str,
+    records: Vec<Record<RoadmapItem>>,
+    today: NaiveDate,
+) -> Validation<RoadmapItem> {
+    let mut validation = Validation::default();
+    let mut invalid: HashSet<String> = HashSet::new();
+    for record in &records {
+        if let Err(errors) = record.value.validate_args(&today) {
+            invalid.insert(record.value.name.clone());
+            validation.problems.extend(rule_problems(
+                file_path,
+                record,
+                record.value.name.as_str(),
+                errors,
+            ));
+        }
+    }
+
+    // Dependencies can only be checked once every item has been read
+    let names: HashSet<&str> = records
+        .iter()
+        .map(|record| record.value.name.as_str())
+        .collect();
+    for record in &records {
+        for dependency in &record.value.depends_on {
+            if !names.contains(dependency.as_str()) {
+                invalid.insert(record.value.name.clone());
+                validation.problems.push(Problem {
+                    record: Some(record.value.name.clone()),
+                    column: Some(String::from("depends_on")),
+                    ..Problem::new(
+                        file_path,
+                        record.line,
+                        format!("Dependency {dependency} is not on the roadmap"),
+                    )
+                });
+            }
+        }
+    }
+    // Dependencies that are not on the roadmap are already reported
+    let roadmap_items: Vec<RoadmapItem> = records
+        .iter()
+        .map(|record| {
+            let mut item = record.value.clone();
+            item.depends_on
+                .retain(|dependency| names.contains(dependency.as_str()));
+            return item;
+        })
+        .collect();
+    if let Err(KapacitetError::DependencyCycle(cycle)) = topological_order(&roadmap_items) {
+        invalid.extend(cycle.iter().cloned());
+        validation.problems.push(Problem {
+            column: Some(String::from("depends_on")),
+            ..Problem::new(
+                file_path,
+                None,
+                format!("Dependencies form a cycle {0}", cycle.join(" -> ")),
+            )
+        });
+    }
+
+    validation.valid = records
+        .iter()
+        .filter(|record| !invalid.contains(&record.value.name))
+        .count();
+    validation.records = records;
+    return validation;
+}
+
+/// Checks the team as a whole: names must be unique and there must be someone to plan with.
+pub fn check_team(
+    file_path: &str,
+    contributors: &[Record<Contributor>],
+    config: &Config,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if contributors.is_empty() {
+        problems.push(Problem::new(
+            file_path,
+            None,
+            String::from("The team has no contributors"),
+        ));
+        return with_severity(problems, config.checks.empty_team);
+    }
+    let names = contributors
+        .iter()
+        .map(|record| (record.value.name.as_str(), record.line));
+    problems.extend(with_severity(
+        duplicate_names(file_path, names),
+        config.checks.duplicate_names,
+    ));
+    return problems;
+}
+
+/// Checks the roadmap as a whole against the team: names must be unique, some open items
+/// must start within the planning horizon, assigned contributors must be on the team and
+/// every target date must be reachable when the contributors with the most capacity work
+/// on the item from its start.
+pub fn check_roadmap_plan(
+    file_path: &str,
+    roadmap_items: &[Record<RoadmapItem>],
+    contributors: &[Contributor],
+    calendar: &Calendar,
+    today: NaiveDate,
+    config: &Config,
+) -> Vec<Problem> {
+    let names = roadmap_items
+        .iter()
+        .map(|record| (record.value.name.as_str(), record.line));
+    let mut problems = with_severity(
+        duplicate_names(file_path, names),
+        config.checks.duplicate_names,
+    );
+
+    let horizon_end = config.horizon_end(today);
+    let in_horizon = roadmap_items
+        .iter()
+        .map(|record| &record.value)
+        .filter(|item| item.is_open())
+        .any(|item| horizon_end.is_none_or(|end| item.start_date <= end));
+    if !in_horizon {
+        let rule = match config.horizon_weeks {
+            Some(weeks) if !roadmap_items.is_empty() => {
+                format!("No roadmap items start within the horizon of {weeks} weeks")
+            }
+            _ => String::from("The roadmap has no items"),
+        };
+        problems.extend(with_severity(
+            vec![Problem::new(file_path, None, rule)],
+            config.checks.empty_horizon,
+        ));
+    }
+
+    let mut unknown = Vec::new();
+    for record in roadmap_items.iter().filter(|record| record.value.is_open()) {
+        let item = &record.value;
+        for name in &item.assigned {
+            if !contributors
+                .iter()
+                .any(|contributor| contributor.name == *name)
+            {
+                unknown.push(Problem {
+                    record: Some(item.name.clone()),
+                    column: Some(String::from("assigned")),
+                    ..Problem::new(
+                        file_path,
+                        record.line,
+                        format!("Assigned contributor {name} is not on the team"),
+                    )
+                });
+            }
+        }
+    }
+    problems.extend(with_severity(unknown, config.checks.unknown_contributors));
+
+    let mut team: Vec<&Contributor> = contributors.iter().collect();
+    team.sort_by(|a, b| b.get_allocation().total_cmp(&a.get_allocation()));
+    let team: Vec<Contributor> = team.into_iter().take(MAX_HEADCOUNT).cloned().collect();
+    if team.is_empty() {
+        return problems;
+    }
+    let mut unreachable = Vec::new();
+    for record in roadmap_items.iter().filter(|record| record.value.is_open()) {
+        let item = &record.value;
+        // Work on items in progress started before today
+        let start = match item.status {
+            Status::InProgress => NaiveDate::min(today, item.start_date),
+            _ => NaiveDate::max(today, item.start_date),
+        };
+        let effort = estimate_effort(item.estimated_complexity);
+        let end = calendar.finish_date(start, effort, &team);
+        if end > item.target_date {
+            unreachable.push(Problem {
+                record: Some(item.name.clone()),
+                column: Some(String::from("target_date")),
+                ..Problem::new(
+                    file_path,
+                    record.line,
+                    format!(
+                        "The target date {0} cannot be met, {1} contributors would finish on {end} at the earliest",
+                        item.target_date,
+                        team.len()
+                    ),
+                )
+            });
+        }
+    }
+    problems.extend(with_severity(
+        unreachable,
+        config.checks.unreachable_target_dates,
+    ));
+    return problems;
+}
+
+/// Counts the problems with the severity of an error.
+pub fn errors(problems: &[Problem]) -> usize {
+    return problems
+        .iter()
+        .filter(|problem| problem.severity == Severity::Error)
+        .count();
+}
+
+// Reports every record after the first with the same name, at its own line
+fn duplicate_names<'a>(
+    file_path: &str,
+    names: impl Iterator<Item = (&'a str, Option<u64>)>,
+) -> Vec<Problem> {
+    let mut seen: HashMap<&str, Option<u64>> = HashMap::new();
+    let mut problems = Vec::new();
+    for (name, line) in names {
+        match seen.get(name) {
+            Some(first) => {
+                let rule = match first {
+                    Some(first) => format!("Names must be unique, {name} is also on line {first}"),
+                    None => String::from("Names must be unique"),
+                };
+                problems.push(Problem {
+                    record: Some(String::from(name)),
+                    column: Some(String::from("name")),
+                    ..Problem::new(file_path, line, rule)
+                });
+            }
+            None => {
+                seen.insert(name, line);
+            }
+        }
+    }
+    return problems;
+}
+
+fn with_severity(problems: Vec<Problem>, severity: Severity) -> Vec<Problem> {
+    if severity == Severity::Off {
+        return Vec::new();
+    }
+    return problems
+        .into_iter()
+        .map(|problem| Problem {
+            severity,
+            ..problem
+        })
+        .collect();
+}
+
+/// Checks the `[[contributors]]` tables of a project file.
+pub(crate) fn check_inline_contributors(
+    file_path: &str,
+    document: toml::Value,
+) -> Validation<Contributor> {
+    let mut problems = Vec::new();
+    let records = inline_records(file_path, document, "contributors", &mut problems);
+    return with_problems(problems, check_contributors(file_path, records));
+}
+
+/// Checks the `[[roadmap]]` tables of a project file.
+pub(crate) fn check_inline_roadmap(
+    file_path: &str,
+    document: toml::Value,
+    today: NaiveDate,
+) -> Validation<RoadmapItem> {
+    let mut problems = Vec::new();
+    let records = inline_records(file_path, document, "roadmap", &mut problems);
+    return with_problems(problems, check_roadmap(file_path, records, today));
+}
+
+fn inline_records<T: DeserializeOwned>(
+    file_path: &str,
+    document: toml::Value,
+    key: &str,
+    problems: &mut Vec<Problem>,
+) -> Vec<Record<T>> {
+    match values_from_toml(file_path, document, key) {
+        Ok(values) => {
+            return records_from_values(file_path, values, problems);
+        }
+        Err(err) => {
+            problems.push(Problem::new(file_path, None, err.to_string()));
+            return Vec::new();
+        }
+    }
+}
+
+// Problems found while reading and the rules the records break, in the order of the file
+fn with_problems<T>(mut problems: Vec<Problem>, mut validation: Validation<T>) -> Validation<T> {
+    problems.append(&mut validation.problems);
+    problems.sort_by_key(|problem| problem.line);
+    validation.problems = problems;
+    return validation;
+}
+
+fn read_records<T: DeserializeOwned>(
+    file_path: &str,
+    format: InputFormat,
+    key: &str,
+    problems: &mut Vec<Problem>,
+) -> Vec<Record<T>> {
+    if format != InputFormat::Csv {
+        let values = fs::read_to_string(file_path)
+            .map_err(|err| KapacitetError::UnreadableFile(String::from(file_path), err.into()))
+            .and_then(|contents| values_from_document(file_path, contents.as_str(), format, key));
+        match values {
+            Ok(values) => {
+                return records_from_values(file_path, values, problems);
+            }
+            Err(err) => {
+                problems.push(Problem::new(file_path, None, err.to_string()));
+                return Vec::new();
+            }
+        }
+    }
+
+    match csv::Reader::from_path(file_path) {
+        Ok(rdr) => {
+            return records_from_csv(file_path, rdr, problems);
+        }
+        Err(err) => {
+            let err = KapacitetError::UnreadableFile(String::from(file_path), err);
+            problems.push(Problem::new(file_path, None, err.to_string()));
+            return Vec::new();
+        }
+    }
+}
+
+fn records_from_csv<T: DeserializeOwned, R: Read>(
+    file_path: &str,
+    mut rdr: csv::Reader<R>,
+    problems: &mut Vec<Problem>,
+) -> Vec<Record<T>> {
+    let headers = match rdr.headers() {
+        Ok(headers) => headers.clone(),
+        Err(err) => {
+            problems.push(csv_problem(file_path, err));
+            return Vec::new();
+        }
+    };
+    let mut records = Vec::new();
+    for result in rdr.records() {
+        let record = match result {
+            Ok(record) => record,
+            Err(err) => {
+                problems.push(csv_problem(file_path, err));
+                continue;
+            }
+        };
+        let line = record.position().map(|position| position.line());
+        match record.deserialize::<T>(Some(&headers)) {
+            Ok(value) => records.push(Record { value, line }),
+            Err(err) => problems.push(record_problem::<T>(file_path, &headers, &record, err)),
+        }
+    }
+    return records;
+}
+
+fn records_from_values<T: DeserializeOwned>(
+    file_path: &str,
+    values: Vec<serde_json::Value>,
+    problems: &mut Vec<Problem>,
+) -> Vec<Record<T>> {
+    let mut records = Vec::new();
+    for (i, value) in values.into_iter().enumerate() {
+        let name = match value.get("name").and_then(|name| name.as_str()) {
+            Some(name) => String::from(name),
+            None => format!("#{0}", i + 1),
+        };
+        match T::deserialize(value) {
+            Ok(value) => records.push(Record { value, line: None }),
+            Err(err) => problems.push(Problem {
+                record: Some(name),
+                ..Problem::new(file_path, None, err.to_string())
+            }),
+        }
+    }
+    return records;
+}
+
+fn csv_problem(file_path: &str, err: csv::Error) -> Problem {
+    let line = err.position().map(|position| position.line());
+    return Problem::new(file_path, line, err.to_string());
+}
+
+// Names the record and the column of a value that cannot be read
+fn record_problem<T: DeserializeOwned>(
+    file_path: &str,
+    headers: &csv::StringRecord,
+    record: &csv::StringRecord,
+    err: csv::Error,
+) -> Problem {
+    let csv::ErrorKind::Deserialize { err: de_err, pos } = err.kind() else {
+        return csv_problem(file_path, err);
+    };
+    // Errors raised by the type of a field, like dates, don't know the field
+    let field = de_err
+        .field()
+        .map(|i| i as usize)
+        .or_else(|| failing_field::<T>(headers, record));
+    let name = ["name", "description"]
+        .iter()
+        .filter_map(|column| headers.iter().position(|header| header == *column))
+        .find_map(|i| record.get(i).filter(|name| !name.is_empty()));
+    let rule = match field.and_then(|i| record.get(i)) {
+        Some(value) => format!("Cannot read {value}: {0}", de_err.kind()),
+        None => de_err.kind().to_string(),
+    };
+    return Problem {
+        record: name.map(String::from),
+        column: field.and_then(|i| headers.get(i)).map(String::from),
+        ..Problem::new(file_path, pos.as_ref().map(|pos| pos.line()), rule)
+    };
+}
+
+// Finds the field whose value fails by leaving out one field at a time. Missing fields are
+// only reported once every field has been read, so leaving out any other field fails the
+// same way.
+fn failing_field<T: DeserializeOwned>(
+    headers: &csv::StringRecord,
+    record: &csv::StringRecord,
+) -> Option<usize> {
+    let error = |left_out: Option<usize>| {
+        let without = |fields: &csv::StringRecord| {
+            return fields
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| Some(*i) != left_out)
+                .map(|(_, field)| field)
+                .collect::<csv::StringRecord>();
+        };
+        return without(record)
+            .deserialize::<T>(Some(&without(headers)))
+            .err()
+            .map(|err| err.to_string());
+    };
+    let original = error(None);
+    return (0..headers.len()).find(|i| error(Some(*i)) != original);
+}
+
+// Turns every failed rule into its own problem, with rules on the whole record first
+fn rule_problems<T>(
+    file_path: &str,
+    record: &Record<T>,
+    name: &str,
+    errors: ValidationErrors,
+) -> Vec<Problem> {
+    let mut fields: Vec<_> = errors.field_errors().into_iter().collect();
+    fields.sort_by_key(|(field, _)| (*field != "__all__", *field));
+    let mut problems = Vec::new();
+    for (field, field_errors) in fields {
+        for error in field_errors {
+            let rule = match &error.message {
+                Some(message) => message.to_string(),
+                None => error.code.to_string(),
+            };
+            problems.push(Problem {
+                record: (!name.is_empty()).then(|| String::from(name)),
+                column: (field != "__all__").then(|| String::from(field)),
+                ..Problem::new(file_path, record.line, rule)
+            });
+        }
+    }
+    return problems;
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{0}", self.file_path)?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        write!(f, ": {0}", self.severity)?;
+        if let Some(record) = &self.record {
+            write!(f, ": {record}")?;
+        }
+        if let Some(column) = &self.column {
+            write!(f, ": {column}")?;
+        }
+        write!(f, ": {0}", self.rule)
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Severity, String> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Severity::Error),
+            "warning" => Ok(Severity::Warning),
+            "off" => Ok(Severity::Off),
+            _ => Err(format!(
+                "Unknown severity {s}. Use one of error, warning or off"
+            )),
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Off => write!(f, "off"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::calendar::Calendar;
+    use crate::config::Config;
+    use crate::contributor::Contributor;
+    use crate::input::{values_from_document, InputFormat};
+    use crate::roadmap::RoadmapItem;
+    use crate::validation::{
+        check_contributors, check_roadmap, check_roadmap_plan, check_team, records_from_csv,
+        records_from_values, with_problems, Record, Severity,
+    };
+
+    #[test]
+    fn reports_every_problem_with_its_position() {
+        let mut problems = Vec::new();
+        let rdr = csv::Reader::from_reader(
+            "name,seniority,capacity\nIC1,3,1\nIC2,7,1\nIC3,x,1\n,0,2\n".as_bytes(),
+        );
+        let records = records_from_csv("team.csv", rdr, &mut problems);
+        let validation = with_problems(problems, check_contributors("team.csv", records));
+
+        assert_eq!(validation.valid, 1);
+        let rules: Vec<(Option<u64>, Option<&str>, &str)> = validation
+            .problems
+            .iter()
+            .map(|problem| {
+                (
+                    problem.line,
+                    problem.column.as_deref(),
+                    problem.rule.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                (
+                    Some(3),
+                    Some("seniority"),
+                    "Seniority must be between 1 and 5"
+                ),
+                (
+                    Some(4),
+                    Some("seniority"),
+                    "Cannot read x: invalid digit found in string"
+                ),
+                (
+                    Some(5),
+                    Some("capacity"),
+                    "Capacity must be between 0.1 and 1"
+                ),
+                (Some(5), Some("name"), "Name cannot be empty"),
+                (
+                    Some(5),
+                    Some("seniority"),
+                    "Seniority must be between 1 and 5"
+                ),
+            ]
+        );
+        assert_eq!(
+            validation.problems[0].to_string(),
+            "team.csv:3: error: IC2: seniority: Seniority must be between 1 and 5"
+        );
+    }
+
+    #[test]
+    fn names_the_record_and_column_of_unreadable_values() {
+        let mut problems = Vec::new();
+        let rdr = csv::Reader::from_reader(
+            "name,estimated_complexity,estimated_value,start_date,target_date\n\
+             MVP,4,5,2022-06-01,2022-13-01\n"
+                .as_bytes(),
+        );
+        let records = records_from_csv::<RoadmapItem, _>("roadmap.csv", rdr, &mut problems);
+        assert!(records.is_empty());
+        assert_eq!(
+            problems[0].to_string(),
+            "roadmap.csv:2: error: MVP: target_date: Cannot read 2022-13-01: input is out of range"
+        );
+    }
+
+    #[test]
+    fn reports_malformed_records_and_unknown_dependencies_in_documents() {
+        let values = values_from_document(
+            "roadmap.json",
+            r#"[
+                {"name": "MVP", "estimated_complexity": 3, "estimated_value": 4,
+                 "start_date": "2022-06-01", "target_date": "2022-12-01", "depends_on": ["Auth"]},
+                {"name": "Docs", "estimated_complexity": 9, "estimated_value": 1,
+                 "start_date": "2022-06-01", "target_date": "2022-12-01"},
+                {"name": "Beta"}
+            ]"#,
+            InputFormat::Json,
+            "roadmap",
+        )
+        .unwrap();
+        let mut problems = Vec::new();
+        let records = records_from_values("roadmap.json", values, &mut problems);
+        let today = NaiveDate::from_ymd_opt(2022, 6, 1).unwrap();
+        let validation = with_problems(problems, check_roadmap("roadmap.json", records, today));
+
+        let locations: Vec<(Option<&str>, Option<&str>)> = validation
+            .problems
+            .iter()
+            .map(|problem| (problem.record.as_deref(), problem.column.as_deref()))
+            .collect();
+        assert_eq!(validation.valid, 0);
+        assert_eq!(
+            locations,
+            [
+                (Some("Beta"), None),
+                (Some("Docs"), Some("estimated_complexity")),
+                (Some("MVP"), Some("depends_on")),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_duplicates_and_unreachable_target_dates_with_their_severity() {
+        let today = NaiveDate::from_ymd_opt(2022, 6, 1).unwrap();
+        let contributors = vec![
+            Contributor::new(String::from("IC1"), 3),
+            Contributor::new(String::from("IC2"), 2),
+            Contributor::new(String::from("IC1"), 5),
+        ];
+        let item = |name: &str, target_date: NaiveDate| {
+            RoadmapItem::new(String::from(name), 5, 3, today, target_date, Vec::new())
+        };
+        let roadmap_items = vec![
+            Record::new(
+                item("MVP", NaiveDate::from_ymd_opt(2022, 7, 1).unwrap()),
+                Some(2),
+            ),
+            Record::new(
+                item("Rewrite", NaiveDate::from_ymd_opt(2022, 6, 10).unwrap()),
+                Some(3),
+            ),
+        ];
+        let team: Vec<Record<Contributor>> = contributors
+            .iter()
+            .zip(2..)
+            .map(|(contributor, line)| Record::new(contributor.clone(), Some(line)))
+            .collect();
+        let mut config = Config::default();
+
+        let problems = check_team("team.csv", &team, &config);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(
+            problems[0].to_string(),
+            "team.csv:4: error: IC1: name: Names must be unique, IC1 is also on line 2"
+        );
+        let problems = check_roadmap_plan(
+            "roadmap.csv",
+            &roadmap_items,
+            &contributors,
+            &Calendar::default(),
+            today,
+            &config,
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].severity, Severity::Warning);
+        assert_eq!(problems[0].record.as_deref(), Some("Rewrite"));
+        assert_eq!(problems[0].line, Some(3));
+
+        config.checks.duplicate_names = Severity::Off;
+        config.checks.empty_team = Severity::Warning;
+        assert!(check_team("team.csv", &team, &config).is_empty());
+        assert_eq!(
+            check_team("team.csv", &[], &config)[0].to_string(),
+            "team.csv: warning: The team has no contributors"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::process::{Command, Output};
+
+// Runs the binary in a fresh project created by `kapacitet init`
+fn run_in_project(name: &str, args: &[&str]) -> Output {
+    let dir: PathBuf = env::temp_dir().join(format!("kapacitet-{name}-{0}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let kapacitet = env!("CARGO_BIN_EXE_kapacitet");
+    let init = Command::new(kapacitet)
+        .args(["init", "--dir"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(init.status.success());
+    let output = Command::new(kapacitet)
+        .args(args)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    return output;
+}
+
+#[test]
+fn prints_json_plans_to_stdout_and_metadata_to_stderr() {
+    let output = run_in_project("json", &["plan", "--output", "json"]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["items"][0]["name"], "MVP");
+
+    let output = run_in_project("csv", &["plan"]);
+    assert!(output.status.success());
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert!(csv.starts_with("name,"));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("# as of: "));
+}